@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use protobuf::{fuzz_parse, SampleMessage};
+
+fuzz_target!(|data: &[u8]| {
+    fuzz_parse::<SampleMessage>(data);
+});
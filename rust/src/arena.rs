@@ -0,0 +1,564 @@
+//! A bump-allocating arena for latency-sensitive callers that want to avoid
+//! a heap allocation per field.
+//!
+//! This crate's generated messages currently own their fields directly
+//! (`String`, `Vec<u8>`, nested owned messages, ...) rather than being
+//! arena-backed, so `Arena` isn't wired into parsing yet. It exists as a
+//! standalone allocator for embedders who want arena-scoped scratch space
+//! -- e.g. a caller on a stack-constrained path that would rather hand the
+//! arena a `&mut [u8; N]` on the stack than let it reach for `malloc` on
+//! the first allocation.
+//!
+//! Note on scratch-space safety: there is no process-global scratch block
+//! here (no `static mut`, no `Once`-guarded singleton) for the UB concerns
+//! that apply to a C `upb_Arena` kernel to latch onto -- every `Arena`
+//! owns its own blocks behind a `RefCell`, scoped to that instance. This
+//! crate has no C/upb kernel underneath it, so there's no `zeroed_block`
+//! to make thread-safe or run-time size; the closest analogue is that
+//! `alloc_bytes` already derives its block size from the caller's request
+//! (see `GrowthPolicy`) rather than a hard-coded constant.
+//!
+//! Likewise, there's no `as_raw_message()`/`from_raw_message(raw, arena)`
+//! to add for upb interop: this `Arena` doesn't back a `upb_Message*`, so
+//! the escape hatch other upb-based libraries would call through has
+//! nothing on the other side to point at. The one arena-to-arena boundary
+//! this crate does have is [`Arena::fuse`], for moving this arena's own
+//! blocks into another instance of itself.
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+
+/// Controls how an `Arena` grows once its current block is exhausted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrowthPolicy {
+    /// Size of the first heap-allocated block. Only used when the arena
+    /// wasn't given a caller-provided block via `with_initial_block`.
+    pub initial_block_size: usize,
+    /// Each new heap block is at least this many times the size of the
+    /// last one, so a long-lived arena needs fewer, larger allocations
+    /// rather than many small ones.
+    pub growth_factor: usize,
+}
+
+impl Default for GrowthPolicy {
+    fn default() -> Self {
+        GrowthPolicy { initial_block_size: 4096, growth_factor: 2 }
+    }
+}
+
+/// Allocates a new block of at least `size` bytes for an `Arena` to grow
+/// into. Passed to `Arena::new_with_alloc` to route growth through a
+/// caller-chosen allocator (an instrumented pool, a memory-limited one,
+/// ...) instead of the default `Vec`-backed one.
+pub type AllocFn = dyn Fn(usize) -> Box<[u8]>;
+
+fn default_alloc(size: usize) -> Box<[u8]> {
+    vec![0u8; size].into_boxed_slice()
+}
+
+enum Block<'a> {
+    Borrowed(&'a mut [u8]),
+    Owned(Box<[u8]>),
+}
+
+/// What [`Arena::install_bytes`] actually did, so a performance-sensitive
+/// caller can tell a zero-copy fuse apart from a fallback deep copy
+/// without re-deriving it from `fuse`'s own return value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Installed {
+    /// `source`'s blocks were moved into the arena; no bytes were copied.
+    Fused,
+    /// `source` couldn't be fused (see `Arena::fuse`'s doc comment), so
+    /// the bytes were deep-copied into the arena instead.
+    DeepCopied,
+}
+
+impl Block<'_> {
+    fn len(&self) -> usize {
+        match self {
+            Block::Borrowed(buf) => buf.len(),
+            Block::Owned(buf) => buf.len(),
+        }
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        match self {
+            Block::Borrowed(buf) => buf.as_mut_ptr(),
+            Block::Owned(buf) => buf.as_mut_ptr(),
+        }
+    }
+}
+
+/// A bump-allocating arena: `alloc_bytes` hands out slices carved out of
+/// a small number of large blocks instead of one allocation per call.
+pub struct Arena<'a> {
+    policy: GrowthPolicy,
+    alloc: Box<AllocFn>,
+    blocks: RefCell<Vec<Block<'a>>>,
+    cursor: Cell<usize>,
+    /// `debug_assertions`-only record of every `(block_index, start, end)`
+    /// range `alloc_bytes` has ever handed out, so overlapping ranges --
+    /// which would mean two live `&mut [u8]`s aliasing the same bytes --
+    /// turn into an immediate panic in tests instead of silent undefined
+    /// behavior. `alloc_bytes`'s own bump-pointer bookkeeping already
+    /// guarantees ranges never overlap (see its `SAFETY` comment); this is
+    /// a belt-and-suspenders check on that guarantee, not a substitute for
+    /// it -- a bug in the bump-pointer math is exactly the kind of thing
+    /// this is meant to catch before it ships. There's no atomic or lock
+    /// here (see this module's doc comment on why there's no
+    /// process-global state to guard): this `RefCell` is the same
+    /// single-threaded, per-instance scratch space `blocks`/`cursor`
+    /// already use.
+    #[cfg(debug_assertions)]
+    issued_ranges: RefCell<Vec<(usize, usize, usize)>>,
+}
+
+impl Arena<'static> {
+    /// An arena that allocates its own blocks, sized by the default
+    /// `GrowthPolicy`.
+    pub fn new() -> Self {
+        Self::with_growth_policy(GrowthPolicy::default())
+    }
+
+    /// Like `new`, but with a custom `GrowthPolicy`.
+    pub fn with_growth_policy(policy: GrowthPolicy) -> Self {
+        Self::new_with_policy_and_alloc(policy, default_alloc)
+    }
+
+    /// Like `new`, but every block the arena grows into is produced by
+    /// `alloc` instead of the default `Vec`-backed allocator, so growth
+    /// can be routed through an instrumented pool or a memory-limited one.
+    pub fn new_with_alloc(alloc: impl Fn(usize) -> Box<[u8]> + 'static) -> Self {
+        Self::new_with_policy_and_alloc(GrowthPolicy::default(), alloc)
+    }
+
+    fn new_with_policy_and_alloc(policy: GrowthPolicy, alloc: impl Fn(usize) -> Box<[u8]> + 'static) -> Self {
+        let first = alloc(policy.initial_block_size);
+        Arena {
+            policy,
+            alloc: Box::new(alloc),
+            blocks: RefCell::new(vec![Block::Owned(first)]),
+            cursor: Cell::new(0),
+            #[cfg(debug_assertions)]
+            issued_ranges: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl Default for Arena<'static> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Arena<'a> {
+    /// Allocates out of `block` until it's exhausted, then falls back to
+    /// heap blocks sized by the default `GrowthPolicy`. Useful for giving
+    /// the arena a stack buffer or other caller-owned scratch space so the
+    /// common case never touches the allocator.
+    pub fn with_initial_block(block: &'a mut [u8]) -> Self {
+        Arena {
+            policy: GrowthPolicy::default(),
+            alloc: Box::new(default_alloc),
+            blocks: RefCell::new(vec![Block::Borrowed(block)]),
+            cursor: Cell::new(0),
+            #[cfg(debug_assertions)]
+            issued_ranges: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn growth_policy(&self) -> GrowthPolicy {
+        self.policy
+    }
+
+    /// Hands out `len` zeroed bytes, valid for as long as the arena is.
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_bytes(&self, len: usize) -> &mut [u8] {
+        let mut blocks = self.blocks.borrow_mut();
+        let last = blocks.len() - 1;
+        let cursor = self.cursor.get();
+        if cursor + len > blocks[last].len() {
+            let grown = blocks[last].len().saturating_mul(self.policy.growth_factor);
+            let new_size = len.max(grown).max(self.policy.initial_block_size);
+            blocks.push(Block::Owned((self.alloc)(new_size)));
+            self.cursor.set(0);
+        }
+
+        let idx = blocks.len() - 1;
+        let offset = self.cursor.get();
+        self.cursor.set(offset + len);
+        let ptr = blocks[idx].as_mut_ptr();
+
+        #[cfg(debug_assertions)]
+        {
+            let mut issued = self.issued_ranges.borrow_mut();
+            let end = offset + len;
+            for &(other_idx, other_start, other_end) in issued.iter() {
+                assert!(
+                    other_idx != idx || end <= other_start || offset >= other_end,
+                    "Arena::alloc_bytes handed out overlapping ranges in block {idx}: \
+                     [{offset}, {end}) aliases an existing [{other_start}, {other_end})"
+                );
+            }
+            issued.push((idx, offset, end));
+        }
+
+        // SAFETY: `ptr` points at `blocks[idx]`'s backing storage, which is
+        // kept alive by `self.blocks` for as long as `self` is (pushing to
+        // `blocks` never moves an existing entry's heap/borrowed buffer,
+        // only the `Block` handle). `offset` only ever increases and we
+        // just checked `offset + len` fits in this block, so every range
+        // `alloc_bytes` hands out is disjoint from every other one.
+        unsafe { core::slice::from_raw_parts_mut(ptr.add(offset), len) }
+    }
+
+    /// Total bytes reserved across all blocks, including the unused tail
+    /// of the current one. For diagnostics, not a hard capacity limit.
+    pub fn bytes_reserved(&self) -> usize {
+        self.blocks.borrow().iter().map(Block::len).sum()
+    }
+
+    /// Moves `other`'s allocated blocks into `self`, so bytes previously
+    /// handed out by `other.alloc_bytes` stay valid for as long as `self`
+    /// is, without copying them. Lets a caller building up a child value in
+    /// its own arena (for latency isolation, or because it didn't yet have
+    /// a parent to allocate out of) later attach it to a parent arena in
+    /// O(1) instead of deep-copying every byte.
+    ///
+    /// `other` keeps working afterward -- it's handed a fresh block of its
+    /// own -- but anything it allocates after the fuse is independent of
+    /// `self` again; this moves existing memory once, it doesn't link the
+    /// two arenas permanently the way a true joint-ownership fuse would.
+    ///
+    /// Returns `false` (and leaves both arenas untouched) if `other` was
+    /// constructed via `with_initial_block`: that block is borrowed from
+    /// the caller, not owned by the arena, so there's nothing for `self` to
+    /// take ownership of. This mirrors `upb_Arena_Fuse`, which likewise
+    /// refuses to fuse an arena that has a non-allocated initial block.
+    pub fn fuse<'b>(&self, other: &Arena<'b>) -> bool {
+        if (self as *const Self).cast::<()>() == (other as *const Arena<'b>).cast::<()>() {
+            return true;
+        }
+
+        let mut other_blocks = other.blocks.borrow_mut();
+        if other_blocks.iter().any(|b| matches!(b, Block::Borrowed(_))) {
+            return false;
+        }
+
+        // Each `Block::Owned` is a plain `Box<[u8]>` with no borrow of its
+        // own, so it can move into `self`'s block list even though `self`
+        // and `other` may carry different `'a` lifetime parameters.
+        let other_cursor = other.cursor.get();
+        let mut self_blocks = self.blocks.borrow_mut();
+        #[cfg(debug_assertions)]
+        let block_offset = self_blocks.len();
+        for block in other_blocks.drain(..) {
+            if let Block::Owned(buf) = block {
+                self_blocks.push(Block::Owned(buf));
+            }
+        }
+        self.cursor.set(other_cursor);
+        drop(self_blocks);
+
+        // The moved blocks' debug-mode issued ranges move with them, with
+        // their block indices shifted by how many blocks `self` already
+        // had -- otherwise a range `other` issued out of its block 0 would
+        // be checked against `self`'s unrelated block 0 after the fuse.
+        #[cfg(debug_assertions)]
+        {
+            let mut other_ranges = other.issued_ranges.borrow_mut();
+            let mut self_ranges = self.issued_ranges.borrow_mut();
+            self_ranges.extend(other_ranges.drain(..).map(|(idx, start, end)| (idx + block_offset, start, end)));
+        }
+
+        other_blocks.push(Block::Owned((other.alloc)(other.policy.initial_block_size)));
+        other.cursor.set(0);
+        true
+    }
+
+    /// Installs `bytes` -- previously allocated out of `source` -- into
+    /// `self`, the way setting a submessage field to an owned value built
+    /// in its own arena would need to: take ownership of the bytes
+    /// without copying them when the two arenas can be fused, and fall
+    /// back to an owned copy when they can't (`source` was built via
+    /// `with_initial_block`, so `fuse` has nothing transferable -- see its
+    /// doc comment). The return value says which happened, for a
+    /// performance-sensitive caller that wants to know without probing
+    /// `source`'s construction itself.
+    ///
+    /// The no-copy path calls `self.fuse(source)`, which -- per `fuse`'s own
+    /// doc comment -- moves *all* of `source`'s owned blocks into `self`,
+    /// not just the one `bytes` happens to live in: `fuse` has no way to
+    /// tell `bytes` apart from anything else `source` has allocated. That's
+    /// the right granularity for `source`'s intended use (a scratch arena
+    /// built for exactly one value, then installed once and discarded --
+    /// the same pattern `upb_Arena_Fuse` itself assumes), but it means
+    /// `source` must not be a shared or longer-lived arena with other data
+    /// a caller still expects to own independently: installing one field's
+    /// bytes out of such an arena reparents everything else it holds too.
+    ///
+    /// There's no `MsgMut<'a, Msg>` submessage proxy in this crate for a
+    /// `set_submessage` method to live on -- generated messages own their
+    /// fields directly rather than pointing into an arena (see this
+    /// module's doc comment) -- so this is the arena-level primitive such
+    /// a proxy would delegate to once one exists, exposed now so callers
+    /// doing their own arena bookkeeping can already avoid the deep copy
+    /// where possible.
+    pub fn install_bytes(&self, bytes: &[u8], source: &Arena<'_>) -> Installed {
+        if self.fuse(source) {
+            Installed::Fused
+        } else {
+            self.alloc_bytes(bytes.len()).copy_from_slice(bytes);
+            Installed::DeepCopied
+        }
+    }
+
+    /// Consumes `self`, returning a `Send + Sync` snapshot of every byte
+    /// allocated so far that any number of threads can read concurrently.
+    ///
+    /// `Arena` itself can't be `Sync` -- `blocks`/`cursor` are
+    /// `RefCell`/`Cell`, the same single-threaded scratch space this
+    /// module's doc comment describes, and no amount of documentation
+    /// changes what the type system allows another thread to touch.
+    /// `freeze` gets to `Send + Sync` the type-system way instead: it
+    /// takes `self` by value, so once an arena is frozen there is no
+    /// `Arena` left for anyone to call `alloc_bytes` through again, and
+    /// the returned `ArenaGuard` only ever hands out `&[u8]`. A block
+    /// borrowed via `with_initial_block` is copied here rather than
+    /// shared, since its lifetime `'a` is tied to a caller-owned buffer
+    /// this arena never owned in the first place.
+    pub fn freeze(self) -> ArenaGuard {
+        let blocks = self.blocks.into_inner();
+        let snapshot = blocks
+            .into_iter()
+            .map(|block| match block {
+                Block::Owned(buf) => buf,
+                Block::Borrowed(buf) => Vec::from(buf).into_boxed_slice(),
+            })
+            .collect();
+        ArenaGuard { blocks: Arc::new(snapshot) }
+    }
+}
+
+/// A read-only, `Send + Sync`, cheaply-cloneable handle to everything an
+/// [`Arena`] had allocated at the point it was [`Arena::freeze`]d.
+///
+/// Cloning shares the same backing blocks (an `Arc` bump, like
+/// [`crate::Frozen`]'s) rather than copying them, so handing a clone to
+/// each of several reader threads is cheap.
+#[derive(Clone)]
+pub struct ArenaGuard {
+    blocks: Arc<Vec<Box<[u8]>>>,
+}
+
+impl ArenaGuard {
+    /// How many blocks the frozen arena had.
+    pub fn block_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Borrows block `index`'s bytes, or `None` if the frozen arena had
+    /// fewer than `index + 1` blocks.
+    pub fn block(&self, index: usize) -> Option<&[u8]> {
+        self.blocks.get(index).map(Box::as_ref)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alloc_bytes_hands_out_disjoint_writable_slices() {
+        let arena = Arena::new();
+        let a = arena.alloc_bytes(4);
+        a.copy_from_slice(b"abcd");
+        let b = arena.alloc_bytes(4);
+        b.copy_from_slice(b"wxyz");
+        assert_eq!(arena.alloc_bytes(0), &[] as &[u8]);
+        assert_eq!(a, b"abcd");
+        assert_eq!(b, b"wxyz");
+    }
+
+    #[test]
+    fn with_initial_block_uses_caller_buffer_before_growing() {
+        let mut stack_buf = [0u8; 8];
+        let arena = Arena::with_initial_block(&mut stack_buf);
+        assert_eq!(arena.bytes_reserved(), 8);
+
+        arena.alloc_bytes(8).copy_from_slice(&[1; 8]);
+        // The initial block is now full; the next allocation must grow.
+        arena.alloc_bytes(1);
+        assert!(arena.bytes_reserved() > 8);
+    }
+
+    #[test]
+    fn new_with_alloc_routes_every_block_through_the_hook() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let requested_sizes = Rc::new(RefCell::new(Vec::new()));
+        let sizes_for_hook = Rc::clone(&requested_sizes);
+        let arena = Arena::new_with_alloc(move |size| {
+            sizes_for_hook.borrow_mut().push(size);
+            vec![0u8; size].into_boxed_slice()
+        });
+
+        arena.alloc_bytes(4096); // exhausts the default-sized first block
+        arena.alloc_bytes(1); // forces growth through the hook again
+
+        assert_eq!(requested_sizes.borrow().len(), 2);
+    }
+
+    #[test]
+    fn freeze_preserves_previously_allocated_bytes() {
+        let arena = Arena::new();
+        arena.alloc_bytes(4).copy_from_slice(b"data");
+
+        let guard = arena.freeze();
+        assert_eq!(&guard.block(0).unwrap()[..4], b"data");
+    }
+
+    #[test]
+    fn freeze_copies_a_borrowed_initial_block_instead_of_sharing_it() {
+        let mut stack_buf = [0u8; 4];
+        stack_buf.copy_from_slice(b"stck");
+        let arena = Arena::with_initial_block(&mut stack_buf);
+
+        let guard = arena.freeze();
+        assert_eq!(guard.block(0), Some(b"stck".as_slice()));
+    }
+
+    #[test]
+    fn arena_guard_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ArenaGuard>();
+    }
+
+    #[test]
+    fn arena_guard_clone_shares_the_same_backing_allocation() {
+        let arena = Arena::new();
+        arena.alloc_bytes(4).copy_from_slice(b"data");
+        let guard = arena.freeze();
+        let shared = guard.clone();
+        assert_eq!(Arc::strong_count(&guard.blocks), 2);
+        assert_eq!(&shared.block(0).unwrap()[..4], b"data");
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "overlapping ranges")]
+    fn alloc_bytes_panics_on_a_manufactured_overlap() {
+        let arena = Arena::new();
+        arena.alloc_bytes(8);
+        // Rewind the cursor by hand to simulate a bug in the bump-pointer
+        // math that would otherwise hand out a range overlapping the one
+        // above -- the debug-mode check should catch it immediately
+        // instead of letting two aliasing `&mut [u8]`s escape.
+        arena.cursor.set(0);
+        arena.alloc_bytes(4);
+    }
+
+    #[test]
+    fn growth_policy_controls_the_first_block_size() {
+        let arena = Arena::with_growth_policy(GrowthPolicy { initial_block_size: 16, growth_factor: 4 });
+        assert_eq!(arena.bytes_reserved(), 16);
+        assert_eq!(arena.growth_policy().growth_factor, 4);
+    }
+
+    #[test]
+    fn fuse_moves_child_bytes_into_the_parent_without_invalidating_them() {
+        let parent = Arena::new();
+        let child = Arena::new();
+        let child_bytes = child.alloc_bytes(4);
+        child_bytes.copy_from_slice(b"kids");
+
+        let parent_reserved_before = parent.bytes_reserved();
+        assert!(parent.fuse(&child));
+
+        // The bytes handed out by `child` before the fuse are unaffected...
+        assert_eq!(child_bytes, b"kids");
+        // ...and the memory they live in is now reserved by `parent`.
+        assert!(parent.bytes_reserved() > parent_reserved_before);
+
+        // `child` keeps working afterward, independently of `parent`.
+        let more = child.alloc_bytes(4);
+        more.copy_from_slice(b"more");
+        assert_eq!(more, b"more");
+    }
+
+    #[test]
+    fn fuse_refuses_an_arena_with_a_borrowed_initial_block() {
+        let parent = Arena::new();
+        let mut stack_buf = [0u8; 8];
+        let child = Arena::with_initial_block(&mut stack_buf);
+
+        assert!(!parent.fuse(&child));
+    }
+
+    #[test]
+    fn fuse_with_self_is_a_no_op_success() {
+        let arena = Arena::new();
+        assert!(arena.fuse(&arena));
+    }
+
+    #[test]
+    fn install_bytes_fuses_when_the_source_arena_allows_it() {
+        let parent = Arena::new();
+        let child = Arena::new();
+        let child_bytes = child.alloc_bytes(4);
+        child_bytes.copy_from_slice(b"data");
+
+        let parent_reserved_before = parent.bytes_reserved();
+        assert_eq!(parent.install_bytes(child_bytes, &child), Installed::Fused);
+        assert!(parent.bytes_reserved() > parent_reserved_before);
+    }
+
+    #[test]
+    fn install_bytes_reparents_the_whole_source_arena_not_just_the_installed_bytes() {
+        let parent = Arena::new();
+        // A small initial block so the second allocation below can't fit
+        // in the same block as the first -- forcing `child` to hold two
+        // separate blocks, so the test can tell "just the installed
+        // bytes' block moved" apart from "the whole arena moved".
+        let child = Arena::with_growth_policy(GrowthPolicy { initial_block_size: 8, growth_factor: 2 });
+        let installed_bytes = child.alloc_bytes(4);
+        installed_bytes.copy_from_slice(b"data");
+        // A second, unrelated allocation in its own block -- `install_bytes`
+        // only takes `installed_bytes`, but `fuse`'s whole-arena
+        // granularity (see `install_bytes`'s doc comment) reparents this
+        // one into `parent` too.
+        let _unrelated_bytes = child.alloc_bytes(1000);
+
+        let child_reserved_before = child.bytes_reserved();
+        let parent_reserved_before = parent.bytes_reserved();
+        assert_eq!(parent.install_bytes(installed_bytes, &child), Installed::Fused);
+
+        // `child` was left with only the fresh block `fuse` hands it back,
+        // not the blocks it had reserved before the fuse -- everything it
+        // held (both blocks) moved to `parent`, not just the one
+        // `installed_bytes` lives in.
+        assert!(child.bytes_reserved() < child_reserved_before);
+        assert!(parent.bytes_reserved() - parent_reserved_before >= child_reserved_before);
+    }
+
+    #[test]
+    fn install_bytes_falls_back_to_a_deep_copy_when_fuse_is_refused() {
+        let parent = Arena::new();
+        let mut stack_buf = [0u8; 4];
+        stack_buf.copy_from_slice(b"data");
+        let child = Arena::with_initial_block(&mut stack_buf);
+        let child_bytes = child.alloc_bytes(4);
+        child_bytes.copy_from_slice(b"data");
+
+        assert_eq!(parent.install_bytes(child_bytes, &child), Installed::DeepCopied);
+
+        // A deep copy doesn't keep `source` linked to `self` the way a
+        // fuse would: mutating `child`'s buffer afterward is safe and has
+        // no bearing on what `parent` now holds a copy of.
+        child_bytes.copy_from_slice(b"gone");
+    }
+}
@@ -0,0 +1,216 @@
+//! `tokio_util::codec::{Encoder, Decoder}` for varint-length-delimited
+//! proto streams, behind the `codec` feature.
+//!
+//! Each frame is `<varint length><message bytes>`, matching the framing
+//! `Message::writeDelimitedTo`/`parseDelimitedFrom` use in the other
+//! language runtimes, so async services can read and write proto streams
+//! without a blocking I/O adapter.
+
+use bytes::{Buf, BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::wire::{decode_varint, encode_varint};
+use crate::ParseError;
+
+/// A length-delimited codec for a single message type `M`.
+///
+/// `M` must round-trip through the same conversions as any other parsing
+/// boundary in this crate: `TryFrom<&[u8]>` to decode and `From<&M> for
+/// Vec<u8>` to encode.
+pub struct LengthDelimitedCodec<M> {
+    max_frame_length: Option<usize>,
+    _marker: std::marker::PhantomData<M>,
+}
+
+impl<M> LengthDelimitedCodec<M> {
+    pub fn new() -> Self {
+        LengthDelimitedCodec { max_frame_length: None, _marker: std::marker::PhantomData }
+    }
+
+    /// Rejects a declared frame length greater than `bytes` with an
+    /// `io::Error` instead of buffering toward it -- this codec's
+    /// equivalent of `ParseOptions::max_message_size`, needed because a
+    /// peer's claimed length prefix is otherwise trusted outright: without
+    /// a cap, a connection that never sends the rest of an oversized frame
+    /// still grows `decode`'s `BytesMut` buffer without bound while
+    /// `decode` waits for the remaining bytes. No limit by default,
+    /// matching `ParseOptions::max_message_size`'s own unbounded default.
+    pub fn max_frame_length(mut self, bytes: usize) -> Self {
+        self.max_frame_length = Some(bytes);
+        self
+    }
+}
+
+impl<M> Default for LengthDelimitedCodec<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M> Encoder<M> for LengthDelimitedCodec<M>
+where
+    Vec<u8>: for<'a> From<&'a M>,
+{
+    type Error = std::io::Error;
+
+    fn encode(&mut self, item: M, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let body = Vec::<u8>::from(&item);
+        let mut len_prefix = Vec::new();
+        encode_varint(body.len() as u64, &mut len_prefix);
+        dst.reserve(len_prefix.len() + body.len());
+        dst.put_slice(&len_prefix);
+        dst.put_slice(&body);
+        Ok(())
+    }
+}
+
+impl<M> Decoder for LengthDelimitedCodec<M>
+where
+    M: for<'a> TryFrom<&'a [u8], Error = ParseError>,
+{
+    type Item = M;
+    type Error = std::io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<M>, Self::Error> {
+        let Some((len, rest)) = decode_varint(src) else { return Ok(None) };
+        let len = len as usize;
+        if let Some(max_frame_length) = self.max_frame_length {
+            if len > max_frame_length {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("frame length {len} exceeds max_frame_length {max_frame_length}"),
+                ));
+            }
+        }
+        let prefix_len = src.len() - rest.len();
+        if rest.len() < len {
+            // Not enough bytes buffered yet for the full frame; wait for more.
+            return Ok(None);
+        }
+
+        src.advance(prefix_len);
+        let body = src.split_to(len);
+        M::try_from(&body).map(Some).map_err(|err| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, format!("malformed proto frame: {err}"))
+        })
+    }
+}
+
+/// Per-message encode/decode, as distinct from `LengthDelimitedCodec`'s
+/// stream framing above: the single operation a tonic/grpcio-style
+/// framework needs to plug a generated message type in directly as its
+/// request/response type, without requiring a `prost::Message` impl.
+///
+/// Blanket-implemented for every type that already round-trips through
+/// this crate's usual parsing boundary (`TryFrom<&[u8]>`/`From<&M> for
+/// Vec<u8>`), so generated messages get it for free.
+pub trait ProtoCodec: Sized {
+    /// Appends this message's wire-format encoding to `buf`.
+    fn encode_to(&self, buf: &mut impl BufMut);
+
+    /// Decodes a message from the whole of `buf`'s remaining bytes.
+    fn decode_from(buf: &mut impl Buf) -> Result<Self, ParseError>;
+}
+
+impl<M> ProtoCodec for M
+where
+    M: for<'a> TryFrom<&'a [u8], Error = ParseError>,
+    Vec<u8>: for<'a> From<&'a M>,
+{
+    fn encode_to(&self, buf: &mut impl BufMut) {
+        buf.put_slice(&Vec::<u8>::from(self));
+    }
+
+    fn decode_from(buf: &mut impl Buf) -> Result<Self, ParseError> {
+        let remaining = buf.copy_to_bytes(buf.remaining());
+        M::try_from(&remaining)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample_gen::SampleMessage;
+
+    #[test]
+    fn encode_then_decode_round_trips_a_message() {
+        let mut codec = LengthDelimitedCodec::<SampleMessage>::new();
+        let message = SampleMessage::new("bob");
+
+        let mut buf = BytesMut::new();
+        codec.encode(message.clone(), &mut buf).unwrap();
+
+        let decoded = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(decoded, message);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn decode_waits_for_a_full_frame_before_returning() {
+        let mut codec = LengthDelimitedCodec::<SampleMessage>::new();
+        let mut buf = BytesMut::new();
+        codec.encode(SampleMessage::new("bob"), &mut buf).unwrap();
+
+        let mut partial = buf.split_to(buf.len() - 1);
+        assert_eq!(codec.decode(&mut partial).unwrap(), None);
+        // The partial frame's bytes must still be buffered, not consumed.
+        assert!(!partial.is_empty());
+    }
+
+    #[test]
+    fn proto_codec_round_trips_through_encode_to_and_decode_from() {
+        let message = SampleMessage::new("bob");
+
+        let mut buf = BytesMut::new();
+        message.encode_to(&mut buf);
+
+        let decoded = SampleMessage::decode_from(&mut buf).unwrap();
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn decode_rejects_a_frame_length_over_the_configured_cap_before_buffering_more() {
+        let mut codec = LengthDelimitedCodec::<SampleMessage>::new().max_frame_length(4);
+        let mut prefix = Vec::new();
+        // A declared length of 5 exceeds the 4-byte cap; only the length
+        // prefix itself needs to be in `buf` to reject it.
+        encode_varint(5, &mut prefix);
+        let mut buf = BytesMut::from(&prefix[..]);
+
+        let result = codec.decode(&mut buf);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn decode_accepts_a_frame_length_at_the_configured_cap() {
+        let message = SampleMessage::new("bob");
+        let mut buf = BytesMut::new();
+        LengthDelimitedCodec::<SampleMessage>::new().encode(message.clone(), &mut buf).unwrap();
+        let (declared_len, _) = decode_varint(&buf).unwrap();
+
+        let mut capped_codec = LengthDelimitedCodec::<SampleMessage>::new().max_frame_length(declared_len as usize);
+        let decoded = capped_codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn decode_reports_malformed_frame_bytes_as_invalid_data() {
+        let mut codec = LengthDelimitedCodec::<SampleMessage>::new();
+        let mut buf = BytesMut::new();
+        // A length-delimited field (tag 1) whose declared length runs past
+        // the end of the frame.
+        let mut frame_body = Vec::new();
+        encode_varint(crate::wire::encode_tag(1, crate::wire::WireType::LengthDelimited), &mut frame_body);
+        encode_varint(10, &mut frame_body);
+        frame_body.extend_from_slice(b"short");
+        let mut framed = Vec::new();
+        encode_varint(frame_body.len() as u64, &mut framed);
+        framed.extend_from_slice(&frame_body);
+        buf.put_slice(&framed);
+
+        let result = codec.decode(&mut buf);
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,184 @@
+//! A chunked byte-string ("rope") representation for large `bytes` fields.
+//!
+//! This crate's generated messages store `bytes` fields as a plain owned
+//! `Vec<u8>` (see [`crate::BytesValue`]), so loading a large payload from
+//! a streaming source the usual way means growing one contiguous buffer
+//! to the payload's full size -- and while it grows, the old and new
+//! backing allocations can coexist during reallocation, temporarily
+//! doubling peak memory for the field. There's no upb/C++ kernel here for
+//! a `ctype=CORD` option to switch on, but the same idea -- keep the
+//! payload as a list of chunks instead of one contiguous buffer -- works
+//! in plain Rust: [`Cord::set_from_reader`] appends fixed-size chunks as
+//! they're read, so peak memory for a 100 MB payload is one chunk plus
+//! whatever's already been appended, not two copies of the whole thing.
+
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use alloc::vec;
+#[cfg(feature = "std")]
+use std::io::{self, Read};
+
+/// A byte string stored as a list of chunks rather than one contiguous
+/// buffer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cord {
+    chunks: Vec<Vec<u8>>,
+    len: usize,
+}
+
+impl Cord {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends a chunk without copying it into a larger buffer.
+    pub fn push_chunk(&mut self, chunk: Vec<u8>) {
+        if chunk.is_empty() {
+            return;
+        }
+        self.len += chunk.len();
+        self.chunks.push(chunk);
+    }
+
+    /// The chunks making up this value, in order.
+    pub fn chunks(&self) -> impl ExactSizeIterator<Item = &[u8]> {
+        self.chunks.iter().map(Vec::as_slice)
+    }
+
+    /// Copies every chunk into one contiguous buffer. Only reach for this
+    /// where a caller genuinely needs a single slice (e.g. handing the
+    /// payload to an API that takes `&[u8]`) -- it reintroduces the
+    /// double-buffer cost `Cord` exists to avoid.
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.len);
+        for chunk in &self.chunks {
+            out.extend_from_slice(chunk);
+        }
+        out
+    }
+}
+
+impl From<Vec<u8>> for Cord {
+    fn from(value: Vec<u8>) -> Self {
+        let mut cord = Cord::new();
+        cord.push_chunk(value);
+        cord
+    }
+}
+
+impl From<Cord> for Vec<u8> {
+    fn from(cord: Cord) -> Vec<u8> {
+        // A single chunk is already exactly the buffer a caller wants;
+        // only multiple chunks need copying into one.
+        let mut chunks = cord.chunks.into_iter();
+        match (chunks.next(), chunks.next()) {
+            (Some(only), None) => only,
+            (first, second) => {
+                let mut out = Vec::with_capacity(cord.len);
+                out.extend(first.into_iter().flatten());
+                out.extend(second.into_iter().flatten());
+                for chunk in chunks {
+                    out.extend(chunk);
+                }
+                out
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Cord {
+    /// Reads `reader` to exhaustion in `chunk_size`-byte pieces, appending
+    /// each one as it arrives instead of growing a single buffer to the
+    /// payload's full size. Replaces any chunks already held.
+    pub fn set_from_reader(&mut self, reader: &mut impl Read, chunk_size: usize) -> io::Result<()> {
+        self.chunks.clear();
+        self.len = 0;
+        let chunk_size = chunk_size.max(1);
+        loop {
+            let mut chunk = vec![0u8; chunk_size];
+            let read = reader.read(&mut chunk)?;
+            if read == 0 {
+                break;
+            }
+            chunk.truncate(read);
+            self.push_chunk(chunk);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_chunk_skips_empty_chunks() {
+        let mut cord = Cord::new();
+        cord.push_chunk(Vec::new());
+        assert!(cord.is_empty());
+        assert_eq!(cord.chunks().len(), 0);
+    }
+
+    #[test]
+    fn to_vec_concatenates_chunks_in_order() {
+        let mut cord = Cord::new();
+        cord.push_chunk(b"ab".to_vec());
+        cord.push_chunk(b"cd".to_vec());
+        assert_eq!(cord.len(), 4);
+        assert_eq!(cord.to_vec(), b"abcd");
+    }
+
+    #[test]
+    fn into_vec_round_trips_a_single_chunk_without_copying() {
+        let cord: Cord = b"payload".to_vec().into();
+        let back: Vec<u8> = cord.into();
+        assert_eq!(back, b"payload");
+    }
+
+    #[test]
+    fn into_vec_concatenates_multiple_chunks() {
+        let mut cord = Cord::new();
+        cord.push_chunk(b"ab".to_vec());
+        cord.push_chunk(b"cd".to_vec());
+        let back: Vec<u8> = cord.into();
+        assert_eq!(back, b"abcd");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn set_from_reader_splits_the_payload_into_fixed_size_chunks() {
+        let mut cord = Cord::new();
+        let mut reader = std::io::Cursor::new(b"abcdefg".to_vec());
+        cord.set_from_reader(&mut reader, 3).unwrap();
+
+        assert_eq!(cord.to_vec(), b"abcdefg");
+        assert_eq!(cord.chunks().len(), 3);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn set_from_reader_replaces_existing_chunks() {
+        let mut cord = Cord::new();
+        cord.push_chunk(b"stale".to_vec());
+
+        let mut reader = std::io::Cursor::new(b"fresh".to_vec());
+        cord.set_from_reader(&mut reader, 16).unwrap();
+
+        assert_eq!(cord.to_vec(), b"fresh");
+    }
+
+    #[test]
+    fn cord_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Cord>();
+    }
+}
@@ -0,0 +1,59 @@
+//! Generic structural diff between two messages of the same type, the Rust
+//! equivalent of C++'s `util::MessageDifferencer` for the simple (no
+//! custom field comparators, no repeated-field matching) case.
+
+use alloc::vec::Vec;
+
+use crate::reflect::{FieldAccess, Reflect};
+
+/// One field that differs between two messages of the same type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+    pub field_name: &'static str,
+    pub left: Option<crate::DynamicValue>,
+    pub right: Option<crate::DynamicValue>,
+}
+
+/// Returns every field where `left` and `right` disagree, in descriptor
+/// field order. An empty result means the messages are equal field-by-field
+/// (unknown fields are not compared; see [`crate::UnknownFieldSet`] for that).
+pub fn diff<M: FieldAccess + Reflect>(left: &M, right: &M) -> Vec<FieldDiff> {
+    M::descriptor()
+        .fields
+        .iter()
+        .filter_map(|field| {
+            let left_value = left.field(field.number);
+            let right_value = right.field(field.number);
+            if left_value != right_value {
+                Some(FieldDiff { field_name: field.name, left: left_value, right: right_value })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample_gen::{Color, SampleMessage};
+
+    #[test]
+    fn diff_is_empty_for_equal_messages() {
+        let a = SampleMessage::new("bob");
+        let b = SampleMessage::new("bob");
+        assert_eq!(diff(&a, &b), vec![]);
+    }
+
+    #[test]
+    fn diff_reports_each_differing_field() {
+        let a = SampleMessage::new("bob");
+        let mut b = SampleMessage::new("alice");
+        b.color = Color::Red;
+
+        let diffs = diff(&a, &b);
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].field_name, "name");
+        assert_eq!(diffs[1].field_name, "color");
+    }
+}
@@ -0,0 +1,144 @@
+//! Dynamic messages: generic field storage driven by a descriptor loaded
+//! at runtime, for tools (e.g. a generic proto pretty-printer) that see a
+//! schema only after the binary has already started and can't get a
+//! generated type for it.
+//!
+//! Note: there's no `OpaqueMiniTable`/FFI layer to cache lookups for here.
+//! `OwnedMessageDescriptor`/`OwnedFieldDescriptor` already are the cached,
+//! owned schema this type reads from directly (`field_by_name` walks a
+//! `Vec`, no per-call resolution through a C kernel), and message-typed
+//! repeated/map fields don't exist on `DynamicMessage` yet -- `DynamicValue`
+//! only covers `String`/`Enum` so far.
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::reflect::FieldType;
+
+/// A field descriptor that owns its name, unlike [`crate::FieldDescriptor`]
+/// which borrows a `'static str` emitted by codegen. Runtime-loaded schemas
+/// (e.g. parsed from a `FileDescriptorProto` fetched over the network)
+/// don't have a `'static` place to borrow from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedFieldDescriptor {
+    pub name: String,
+    pub number: u32,
+    pub field_type: FieldType,
+}
+
+/// The owned equivalent of [`crate::MessageDescriptor`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OwnedMessageDescriptor {
+    pub name: String,
+    pub fields: Vec<OwnedFieldDescriptor>,
+}
+
+impl OwnedMessageDescriptor {
+    pub fn field_by_name(&self, name: &str) -> Option<&OwnedFieldDescriptor> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+}
+
+/// One field's value in a [`DynamicMessage`]. Intentionally a small,
+/// closed set matching [`FieldType`] rather than an open `Any`, since that
+/// keeps `get`/`set` total over the descriptor instead of panicking on a
+/// type mismatch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DynamicValue {
+    String(String),
+    Enum(i32),
+}
+
+impl DynamicValue {
+    /// Whether this is the proto3 default for its type (`""` for strings,
+    /// `0` for enums) -- the closest thing this crate has to field
+    /// presence, since generated messages store plain values with no
+    /// separate has-bit. Used by [`crate::reflect::FieldPresence`] and
+    /// [`crate::reflect::FieldAccess::field_byte_size`], both of which
+    /// need to know whether `serialize()` would skip the field entirely.
+    pub(crate) fn is_default(&self) -> bool {
+        match self {
+            DynamicValue::String(s) => s.is_empty(),
+            DynamicValue::Enum(n) => *n == 0,
+        }
+    }
+}
+
+/// A message whose fields are driven entirely by a runtime [`OwnedMessageDescriptor`]
+/// rather than a generated Rust struct.
+#[derive(Debug, Clone, Default)]
+pub struct DynamicMessage {
+    descriptor: OwnedMessageDescriptor,
+    fields: BTreeMap<u32, DynamicValue>,
+}
+
+impl DynamicMessage {
+    pub fn new(descriptor: OwnedMessageDescriptor) -> Self {
+        DynamicMessage { descriptor, fields: BTreeMap::new() }
+    }
+
+    pub fn descriptor(&self) -> &OwnedMessageDescriptor {
+        &self.descriptor
+    }
+
+    pub fn get(&self, field_name: &str) -> Option<&DynamicValue> {
+        let number = self.descriptor.field_by_name(field_name)?.number;
+        self.fields.get(&number)
+    }
+
+    /// Sets a field by name. Returns `Err` if the descriptor has no such
+    /// field, or if `value`'s variant doesn't match the field's declared
+    /// [`FieldType`].
+    pub fn set(&mut self, field_name: &str, value: DynamicValue) -> Result<(), String> {
+        let field = self
+            .descriptor
+            .field_by_name(field_name)
+            .ok_or_else(|| format!("no such field: {field_name:?}"))?;
+        let matches = matches!(
+            (field.field_type, &value),
+            (FieldType::String, DynamicValue::String(_)) | (FieldType::Enum, DynamicValue::Enum(_))
+        );
+        if !matches {
+            return Err(format!("value does not match declared type of field {field_name:?}"));
+        }
+        self.fields.insert(field.number, value);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn person_descriptor() -> OwnedMessageDescriptor {
+        OwnedMessageDescriptor {
+            name: "Person".to_string(),
+            fields: vec![OwnedFieldDescriptor {
+                name: "name".to_string(),
+                number: 1,
+                field_type: FieldType::String,
+            }],
+        }
+    }
+
+    #[test]
+    fn set_then_get_round_trips_by_field_name() {
+        let mut message = DynamicMessage::new(person_descriptor());
+        message.set("name", DynamicValue::String("bob".to_string())).unwrap();
+        assert_eq!(message.get("name"), Some(&DynamicValue::String("bob".to_string())));
+    }
+
+    #[test]
+    fn set_rejects_unknown_field_names() {
+        let mut message = DynamicMessage::new(person_descriptor());
+        assert!(message.set("missing", DynamicValue::String(String::new())).is_err());
+    }
+
+    #[test]
+    fn set_rejects_mismatched_value_types() {
+        let mut message = DynamicMessage::new(person_descriptor());
+        assert!(message.set("name", DynamicValue::Enum(1)).is_err());
+    }
+}
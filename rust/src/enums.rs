@@ -0,0 +1,51 @@
+//! Helpers shared by all generated enum types.
+
+use core::convert::TryFrom;
+use core::fmt;
+
+/// The numeric value did not match any enumerator declared on the proto
+/// enum. Proto3 enums are "open": unknown values are preserved on the wire
+/// but have no name, so code that needs to round-trip them constructs this
+/// error instead of panicking or silently clamping the value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownEnumValue(pub i32);
+
+impl fmt::Display for UnknownEnumValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not a declared enumerator for this enum", self.0)
+    }
+}
+
+impl core::error::Error for UnknownEnumValue {}
+
+/// Implemented by every generated `enum` type.
+///
+/// The Rust plugin emits one `impl Enum for Foo` per proto enum, alongside
+/// the inherent `#[repr(i32)]` definition and `impl TryFrom<i32>` /
+/// `impl From<Foo> for i32`, so that generic code (CLI flag parsing,
+/// logging, text/JSON formatting) can work with any proto enum without a
+/// per-type match statement.
+pub trait Enum: Copy + Eq + Into<i32> + TryFrom<i32, Error = UnknownEnumValue> + 'static {
+    /// All declared values, in declaration order. Aliases (two names for
+    /// the same number under `allow_alias`) appear once, under their first
+    /// declared name.
+    const VALUES: &'static [Self];
+
+    /// The declared name of this value, or `None` if the numeric value
+    /// does not correspond to any declared enumerator (open enums allow
+    /// unknown values to round-trip without a name).
+    fn name(self) -> Option<&'static str>;
+
+    /// Looks up a value by its declared name. Returns `None` if no
+    /// enumerator with that name exists.
+    fn from_name(name: &str) -> Option<Self>;
+
+    /// Whether `value` names a declared enumerator, without constructing
+    /// one. Lets callers write `NestedEnum::is_known(raw)` as a `matches!`-
+    /// style guard (validating a field before storing it, deciding whether
+    /// to log "unknown enum value") without needing `Self::try_from(raw)`
+    /// and then immediately discarding the `Ok` value.
+    fn is_known(value: i32) -> bool {
+        Self::try_from(value).is_ok()
+    }
+}
@@ -0,0 +1,291 @@
+//! Proto2 extension fields: `get_extension`/`set_extension`/`has_extension`/
+//! `clear_extension` on [`Message`], for an extendable message's field
+//! numbers the schema doesn't declare by name.
+//!
+//! There's no upb extension registry to wire these into -- as `lib.rs`'s
+//! doc comment says, this crate has no C++/upb kernel underneath at all --
+//! and no separate per-message extension table either: on the wire, an
+//! extension field is indistinguishable from any other field a message's
+//! schema doesn't declare, so it's stored in the same [`UnknownFieldSet`]
+//! every message already carries for that purpose (see
+//! `unknown_fields.rs`). An [`ExtensionId`] just remembers which field
+//! number and Rust type a particular extension uses; `get_extension` and
+//! friends decode/encode that field number's raw bytes within
+//! `unknown_fields` without the message type itself needing to know the
+//! extension exists, the same way generated code never needs to know about
+//! a field it doesn't declare to preserve it.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::message::Message;
+use crate::unknown_fields::UnknownField;
+use crate::wire::{decode_varint, encode_varint, Tag, WireType};
+
+/// Identifies one proto2 extension field on message type `M`: its wire
+/// field number, and the Rust type `get_extension`/`set_extension`
+/// decode/encode it as. Pinning `M` means passing a `Foo` extension id to
+/// a `Bar::get_extension` call is a compile error instead of a
+/// runtime field-number coincidence -- generated code would declare one
+/// `pub const` per `extend` block entry, the same way `FieldDescriptor`
+/// constants describe a message's own fields.
+pub struct ExtensionId<M, T> {
+    field_number: u32,
+    _marker: core::marker::PhantomData<fn() -> (M, T)>,
+}
+
+impl<M, T> ExtensionId<M, T> {
+    pub const fn new(field_number: u32) -> Self {
+        ExtensionId { field_number, _marker: core::marker::PhantomData }
+    }
+
+    pub fn field_number(&self) -> u32 {
+        self.field_number
+    }
+}
+
+// Manual rather than `#[derive(Clone, Copy)]`: a derive would add
+// `M: Clone`/`T: Clone` bounds neither field actually needs, since
+// `field_number` is a plain `u32` and `_marker`'s `fn() -> (M, T)` is
+// `Copy` regardless of `M`/`T`.
+impl<M, T> Clone for ExtensionId<M, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M, T> Copy for ExtensionId<M, T> {}
+
+/// A Rust type an extension field can hold, with the wire encoding to
+/// store it as an ordinary unknown field's raw bytes.
+///
+/// Implemented for the scalar types this crate already has generic wire
+/// encoding for elsewhere (`bool`/`i32`/`i64`/`u32`/`u64` as plain varints,
+/// `String`/`Vec<u8>` as length-delimited) -- not `f32`/`f64`/the `sint`
+/// or `fixed` variants, none of which any generated field in this crate
+/// uses yet (see `wire.rs`'s doc comment on `encode_zigzag` being
+/// similarly exposed ahead of use).
+pub trait ExtensionValue: Sized {
+    const WIRE_TYPE: WireType;
+
+    fn decode_extension(raw: &[u8]) -> Option<Self>;
+    fn encode_extension(&self, out: &mut Vec<u8>);
+}
+
+macro_rules! impl_varint_extension_value {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl ExtensionValue for $ty {
+                const WIRE_TYPE: WireType = WireType::Varint;
+
+                fn decode_extension(raw: &[u8]) -> Option<Self> {
+                    let (value, rest) = decode_varint(raw)?;
+                    if !rest.is_empty() {
+                        return None;
+                    }
+                    Some(value as $ty)
+                }
+
+                fn encode_extension(&self, out: &mut Vec<u8>) {
+                    encode_varint(*self as u64, out);
+                }
+            }
+        )+
+    };
+}
+
+impl_varint_extension_value!(i32, i64, u32, u64);
+
+impl ExtensionValue for bool {
+    const WIRE_TYPE: WireType = WireType::Varint;
+
+    fn decode_extension(raw: &[u8]) -> Option<Self> {
+        let (value, rest) = decode_varint(raw)?;
+        if !rest.is_empty() {
+            return None;
+        }
+        Some(value != 0)
+    }
+
+    fn encode_extension(&self, out: &mut Vec<u8>) {
+        encode_varint(*self as u64, out);
+    }
+}
+
+impl ExtensionValue for String {
+    const WIRE_TYPE: WireType = WireType::LengthDelimited;
+
+    fn decode_extension(raw: &[u8]) -> Option<Self> {
+        Some(String::from_utf8_lossy(raw).into_owned())
+    }
+
+    fn encode_extension(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl ExtensionValue for Vec<u8> {
+    const WIRE_TYPE: WireType = WireType::LengthDelimited;
+
+    fn decode_extension(raw: &[u8]) -> Option<Self> {
+        Some(raw.to_vec())
+    }
+
+    fn encode_extension(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self);
+    }
+}
+
+/// [`Message::get_extension`]'s implementation, free-standing so it can be
+/// called from the trait's default method without every implementor
+/// having to repeat it.
+pub(crate) fn get<T: ExtensionValue>(fields: &crate::unknown_fields::UnknownFieldSet, field_number: u32) -> Option<T> {
+    fields
+        .iter()
+        .rev()
+        .find(|field| field.tag.field_number == field_number && field.tag.wire_type == T::WIRE_TYPE)
+        .and_then(|field| T::decode_extension(&field.raw_value))
+}
+
+/// [`Message::set_extension`]'s implementation -- see [`get`].
+pub(crate) fn set<T: ExtensionValue>(
+    fields: &mut crate::unknown_fields::UnknownFieldSet,
+    field_number: u32,
+    value: &T,
+) {
+    fields.remove_field_number(field_number);
+    let mut raw_value = Vec::new();
+    value.encode_extension(&mut raw_value);
+    fields.push(UnknownField { tag: Tag { field_number, wire_type: T::WIRE_TYPE }, raw_value });
+}
+
+/// Extension accessors for any [`Message`], generic over the extension's
+/// value type. A blanket impl rather than new required methods on
+/// [`Message`] itself, so adding extension support doesn't ask every
+/// existing `impl Message` to grow new methods.
+pub trait MessageExt: Message {
+    /// The value of extension `id`, if `self` carries it -- absent either
+    /// because it was never set, or because it's present with a different
+    /// wire type than `id` expects (treated the same as absent, rather
+    /// than a parse error, since a mismatched extension id is a caller
+    /// bug this API can't distinguish from "not set").
+    fn get_extension<T: ExtensionValue>(&self, id: ExtensionId<Self, T>) -> Option<T>
+    where
+        Self: Sized,
+    {
+        get(self.unknown_fields(), id.field_number())
+    }
+
+    /// Sets extension `id` to `value`, replacing any previous value (for
+    /// this id or a mismatched wire type at the same field number) the
+    /// same way a generated field's setter replaces its prior value.
+    fn set_extension<T: ExtensionValue>(&mut self, id: ExtensionId<Self, T>, value: T)
+    where
+        Self: Sized,
+    {
+        set(self.unknown_fields_mut(), id.field_number(), &value);
+    }
+
+    /// Whether `self` carries a value for extension `id`. Defers to the same
+    /// wire-type-aware lookup `get_extension` uses, rather than just
+    /// matching `id`'s field number, so a field number collision with a
+    /// different wire type (some other extension, or an ordinary unknown
+    /// field) can't make this return `true` when `get_extension` would
+    /// return `None` for the same `id`.
+    fn has_extension<T: ExtensionValue>(&self, id: ExtensionId<Self, T>) -> bool
+    where
+        Self: Sized,
+    {
+        get::<T>(self.unknown_fields(), id.field_number()).is_some()
+    }
+
+    /// Removes extension `id`'s value, if present.
+    fn clear_extension<T: ExtensionValue>(&mut self, id: ExtensionId<Self, T>)
+    where
+        Self: Sized,
+    {
+        self.unknown_fields_mut().remove_field_number(id.field_number());
+    }
+}
+
+impl<M: Message> MessageExt for M {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample_gen::SampleMessage;
+
+    const NOTE: ExtensionId<SampleMessage, String> = ExtensionId::new(1000);
+    const PRIORITY: ExtensionId<SampleMessage, i32> = ExtensionId::new(1001);
+
+    fn sample_message(name: &str) -> SampleMessage {
+        let mut message = SampleMessage::default();
+        message.name = name.to_string();
+        message
+    }
+
+    #[test]
+    fn unset_extension_is_absent() {
+        let message = sample_message("bob");
+        assert!(!message.has_extension(NOTE));
+        assert_eq!(message.get_extension(NOTE), None);
+    }
+
+    #[test]
+    fn set_extension_round_trips_through_get_extension() {
+        let mut message = sample_message("bob");
+        message.set_extension(NOTE, String::from("urgent"));
+        assert!(message.has_extension(NOTE));
+        assert_eq!(message.get_extension(NOTE), Some(String::from("urgent")));
+    }
+
+    #[test]
+    fn has_extension_agrees_with_get_extension_on_a_wire_type_mismatch() {
+        // Field 1000 is set as a `String` (`LengthDelimited`) extension, but
+        // looked up here through an `i32` (`Varint`) id sharing the same
+        // field number -- `has_extension` must not report `true` for an id
+        // `get_extension` can't actually produce a value for.
+        let mismatched_id: ExtensionId<SampleMessage, i32> = ExtensionId::new(NOTE.field_number());
+        let mut message = sample_message("bob");
+        message.set_extension(NOTE, String::from("urgent"));
+
+        assert_eq!(message.get_extension(mismatched_id), None);
+        assert!(!message.has_extension(mismatched_id));
+    }
+
+    #[test]
+    fn set_extension_replaces_a_previous_value_instead_of_appending() {
+        let mut message = sample_message("bob");
+        message.set_extension(PRIORITY, 1);
+        message.set_extension(PRIORITY, 2);
+        assert_eq!(message.get_extension(PRIORITY), Some(2));
+        assert_eq!(message.unknown_fields().len(), 1);
+    }
+
+    #[test]
+    fn clear_extension_removes_the_value() {
+        let mut message = sample_message("bob");
+        message.set_extension(PRIORITY, 5);
+        message.clear_extension(PRIORITY);
+        assert!(!message.has_extension(PRIORITY));
+        assert_eq!(message.get_extension(PRIORITY), None);
+    }
+
+    #[test]
+    fn distinct_extensions_coexist() {
+        let mut message = sample_message("bob");
+        message.set_extension(NOTE, String::from("urgent"));
+        message.set_extension(PRIORITY, 7);
+        assert_eq!(message.get_extension(NOTE), Some(String::from("urgent")));
+        assert_eq!(message.get_extension(PRIORITY), Some(7));
+    }
+
+    #[test]
+    fn extension_values_survive_parse_mutate_serialize() {
+        let mut message = sample_message("bob");
+        message.set_extension(PRIORITY, 9);
+
+        let reparsed = SampleMessage::parse(&message.serialize());
+        assert_eq!(reparsed.get_extension(PRIORITY), Some(9));
+    }
+}
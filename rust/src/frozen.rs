@@ -0,0 +1,71 @@
+//! `Frozen<M>`: a cheaply-cloneable, read-only handle to a message shared
+//! across threads, e.g. a parsed config loaded once and handed to every
+//! worker without copying it per-thread.
+
+use alloc::sync::Arc;
+use core::ops::Deref;
+
+/// A read-only, `Clone`-cheap handle to a message.
+///
+/// `Frozen<M>` only exposes `&M` (via `Deref`), never `&mut M`, so a clone
+/// shares the same backing allocation rather than copying it. `Frozen<M>`
+/// is `Send + Sync` whenever `M` is, since the only shared state is the
+/// `Arc`'s refcount.
+pub struct Frozen<M> {
+    inner: Arc<M>,
+}
+
+impl<M> Frozen<M> {
+    pub fn new(message: M) -> Self {
+        Frozen { inner: Arc::new(message) }
+    }
+}
+
+impl<M> Clone for Frozen<M> {
+    fn clone(&self) -> Self {
+        Frozen { inner: Arc::clone(&self.inner) }
+    }
+}
+
+impl<M> Deref for Frozen<M> {
+    type Target = M;
+
+    fn deref(&self) -> &M {
+        &self.inner
+    }
+}
+
+/// Adds `.freeze()` to every generated message type.
+pub trait Freeze: crate::Message + Sized {
+    fn freeze(self) -> Frozen<Self> {
+        Frozen::new(self)
+    }
+}
+
+impl<M: crate::Message> Freeze for M {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample_gen::SampleMessage;
+
+    #[test]
+    fn freeze_exposes_read_only_field_access() {
+        let frozen = SampleMessage::new("bob").freeze();
+        assert_eq!(frozen.name, "bob");
+    }
+
+    #[test]
+    fn clone_shares_the_same_backing_allocation() {
+        let frozen = SampleMessage::new("bob").freeze();
+        let shared = frozen.clone();
+        assert_eq!(Arc::strong_count(&frozen.inner), 2);
+        assert_eq!(shared.name, "bob");
+    }
+
+    #[test]
+    fn frozen_message_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Frozen<SampleMessage>>();
+    }
+}
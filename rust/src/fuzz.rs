@@ -0,0 +1,52 @@
+//! Parse/serialize round-trip fuzzing harnesses, behind the `fuzz`
+//! feature.
+//!
+//! This crate has no FFI boundary to fuzz across -- generated messages
+//! are plain Rust values all the way down to `wire.rs` (see
+//! `message.rs`'s doc comment) -- so the surface worth fuzzing
+//! continuously is the one real boundary every message type crosses:
+//! parsing arbitrary bytes, and the round trip of re-serializing what
+//! came out. [`fuzz_parse`] is the shared harness the cargo-fuzz targets
+//! in `fuzz/fuzz_targets/` call into for each generated message type.
+
+use alloc::vec::Vec;
+
+use crate::message::ParseError;
+
+/// Parses `data` as a wire-format `M` and, if that succeeds, checks that
+/// re-serializing and re-parsing reproduces the same value -- catching
+/// bugs where a message parses one way but doesn't round-trip (e.g. an
+/// unknown field lost on reserialize, a repeated/map field reordered).
+///
+/// Malformed input is expected and ignored, the same as any other
+/// `try_parse` call; only a round-trip mismatch, or a previously-valid
+/// encoding that fails to reparse, panics.
+pub fn fuzz_parse<M>(data: &[u8])
+where
+    M: for<'a> TryFrom<&'a [u8], Error = ParseError> + PartialEq + core::fmt::Debug,
+    Vec<u8>: for<'a> From<&'a M>,
+{
+    let Ok(parsed) = M::try_from(data) else { return };
+    let reserialized = Vec::<u8>::from(&parsed);
+    let reparsed = M::try_from(reserialized.as_slice())
+        .expect("a message that parsed once must reserialize into something that parses again");
+    assert_eq!(parsed, reparsed, "parse -> serialize -> parse produced a different value");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample_gen::SampleMessage;
+
+    #[test]
+    fn fuzz_parse_ignores_malformed_input() {
+        fuzz_parse::<SampleMessage>(&[0xff, 0xff, 0xff]);
+    }
+
+    #[test]
+    fn fuzz_parse_accepts_a_valid_round_tripping_message() {
+        let message = SampleMessage::new("bob");
+        let wire: Vec<u8> = Vec::from(&message);
+        fuzz_parse::<SampleMessage>(&wire);
+    }
+}
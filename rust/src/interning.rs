@@ -0,0 +1,79 @@
+//! Hash-consing for repeated string values.
+//!
+//! This crate's generated messages store string fields as plain owned
+//! `String`s (see `message.rs`'s doc comment: fields are held directly,
+//! not borrowed from an arena), so there's no field-storage hook to
+//! intern through yet -- a `name: String` field can't hold a shared
+//! `Rc<str>` without changing its type. [`StringInterner`] is a
+//! standalone pool for callers who want to dedupe the strings behind
+//! repeated field values before they're stored -- e.g. a telemetry
+//! pipeline building many messages out of a small, highly repetitive set
+//! of label strings -- at the `Rc<str>` level, sharing one allocation per
+//! distinct value instead of giving each occurrence its own `String`.
+
+use alloc::collections::BTreeSet;
+use alloc::rc::Rc;
+
+/// A pool of interned strings, deduplicated by content.
+#[derive(Debug, Default)]
+pub struct StringInterner {
+    pool: BTreeSet<Rc<str>>,
+}
+
+impl StringInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the pool's existing allocation for `value` if one has been
+    /// interned before; otherwise allocates one, adds it to the pool, and
+    /// returns it. Every call with an equal string returns a clone of the
+    /// same `Rc`, so repeated values share one allocation.
+    pub fn intern(&mut self, value: &str) -> Rc<str> {
+        if let Some(existing) = self.pool.get(value) {
+            return Rc::clone(existing);
+        }
+        let interned: Rc<str> = Rc::from(value);
+        self.pool.insert(Rc::clone(&interned));
+        interned
+    }
+
+    /// The number of distinct strings currently pooled.
+    pub fn len(&self) -> usize {
+        self.pool.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pool.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interning_the_same_value_twice_shares_one_allocation() {
+        let mut interner = StringInterner::new();
+        let first = interner.intern("label");
+        let second = interner.intern("label");
+
+        assert!(Rc::ptr_eq(&first, &second));
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn interning_distinct_values_grows_the_pool() {
+        let mut interner = StringInterner::new();
+        interner.intern("a");
+        interner.intern("b");
+        interner.intern("a");
+
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn new_interner_is_empty() {
+        assert!(StringInterner::new().is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+//! Rust runtime support for Protocol Buffers.
+//!
+//! This crate is linked by code emitted from the Rust protoc plugin. It is
+//! not meant to be used directly by hand-written message types; the
+//! generated code relies on the types here to implement field access,
+//! (de)serialization and the other behaviors protobuf messages support
+//! across languages.
+//!
+//! Builds `#![no_std]` + `alloc` when the default `std` feature is
+//! disabled, so the runtime can be linked into embedded Android
+//! components that already bring their own allocator. The pieces that
+//! inherently need an OS -- `Timestamp`'s `SystemTime` conversions, and
+//! the `json`/`serde`/`codec` integrations -- pull `std` back in through
+//! their own feature.
+//!
+//! Nothing in this crate spawns threads or otherwise assumes a particular
+//! target: `Arena` is `!Send`/`!Sync` by construction (its `Cell`/`RefCell`
+//! fields make it `!Sync`, and its boxed [`AllocFn`] hook, which carries
+//! no `Send` bound, makes it `!Send`; its bytes cross threads only once
+//! frozen into an [`ArenaGuard`]'s `Arc`), so there's no scratch-space thread machinery to
+//! gate out under `wasm32-unknown-unknown`/`wasm32-wasip1`, unlike a C/upb
+//! kernel build. `cargo build --target wasm32-unknown-unknown` should work
+//! with the default features disabled (this crate has no way to vendor
+//! the `wasm32` standard library component to verify that in this repo's
+//! own CI config, so it isn't wired into `Cargo.toml` here).
+//!
+//! Note: there's no `Kernel::{Cpp, Upb}` choice to introspect here, since
+//! this crate doesn't link either the C++ or upb kernel -- it's a single,
+//! pure-Rust implementation. A `kernel()` API and cross-kernel parity
+//! tests would have nothing to distinguish until a second backend exists.
+//!
+//! Note: a `protoc --rust_out` option controlling per-message module
+//! layout (one file per message vs. one inline submodule per message,
+//! for incremental-compilation/IDE performance on very large `.proto`
+//! files) belongs in the Rust codegen plugin, not this runtime crate --
+//! and as `sample_gen.rs`'s doc comment says, this snapshot of the tree
+//! predates that plugin, so there's no `.proto` -> `.rs` pipeline here to
+//! add the option to. Nothing in this crate assumes a particular module
+//! layout for generated code (it only requires that the type implement
+//! [`Message`], [`FieldAccess`], etc., regardless of which module it
+//! lives in), so the runtime itself needs no change to support either
+//! layout once the plugin exists.
+//!
+//! Note: likewise, a plugin option to inject extra attributes (a custom
+//! derive, `#[non_exhaustive]`) onto generated structs/enums is a codegen
+//! concern with no pipeline here to add it to -- see the module-layout
+//! note above. The runtime-side half of that gap is already covered: a
+//! hand-rolled `impl Serialize for SampleMessage` in `serde_support.rs`
+//! exists precisely because the generator's derives can't express a
+//! proto message's serde shape (field names, `oneof`/unrecognized-enum
+//! handling) on their own, so any in-house framework needing a custom
+//! derive on the generated struct itself is choosing *codegen-time*
+//! attribute injection over a runtime-side hand-written impl like that
+//! one -- a tradeoff the plugin would need to make, not this crate.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod arena;
+#[cfg(feature = "codec")]
+mod codec;
+mod conformance;
+mod cord;
+mod differencer;
+mod dynamic;
+mod enums;
+mod extensions;
+mod frozen;
+#[cfg(feature = "fuzz")]
+mod fuzz;
+mod interning;
+mod map;
+mod message;
+mod optional;
+mod pool;
+mod primitive_mut;
+#[cfg(feature = "prost")]
+mod prost_interop;
+mod reflect;
+mod repeated;
+mod sample_gen;
+#[cfg(feature = "serde")]
+mod serde_support;
+mod serialized_data;
+mod text_format;
+mod unknown_fields;
+mod well_known_types;
+mod wire;
+
+pub use arena::{AllocFn, Arena, ArenaGuard, GrowthPolicy, Installed};
+#[cfg(feature = "codec")]
+pub use codec::{LengthDelimitedCodec, ProtoCodec};
+pub use cord::Cord;
+pub use differencer::{diff, FieldDiff};
+pub use dynamic::{DynamicMessage, DynamicValue, OwnedFieldDescriptor, OwnedMessageDescriptor};
+pub use enums::{Enum, UnknownEnumValue};
+pub use extensions::{ExtensionId, ExtensionValue, MessageExt};
+pub use frozen::{Freeze, Frozen};
+#[cfg(feature = "fuzz")]
+pub use fuzz::fuzz_parse;
+pub use interning::StringInterner;
+pub use map::{Map, MapKey, OccupiedError};
+pub use message::{
+    leak, parse, parse_from_slices, parse_validated, serialize, transcode, CopyFrom, Digest, Message,
+    ParseError, ParseOptions, Reusable, ValidationError, Validator,
+};
+#[cfg(feature = "metrics")]
+pub use message::{parse_instrumented, serialize_instrumented, Recorder};
+pub use optional::Optional;
+pub use pool::{MessagePool, PooledMessage};
+pub use primitive_mut::PrimitiveMut;
+#[cfg(feature = "prost")]
+pub use prost_interop::{from_prost, to_prost};
+pub use reflect::{
+    get_path, EnumOpenness, FieldAccess, FieldDescriptor, FieldPresenceMode, FieldType,
+    MessageDescriptor, Reflect, ResolvedFeatures, Utf8Validation,
+};
+#[cfg(feature = "field-presence-debug")]
+pub use reflect::FieldPresence;
+pub use repeated::Repeated;
+// `SampleMessage` is this crate's own hand-written stand-in for generated
+// code (see `sample_gen.rs`'s doc comment) and isn't otherwise part of
+// the public API; it's exposed here only so the cargo-fuzz targets under
+// `fuzz/`, which depend on this crate like any other external consumer,
+// have a concrete message type to fuzz.
+#[cfg(feature = "fuzz")]
+pub use sample_gen::{BufferTooSmall, SampleMessage};
+pub use serialized_data::SerializedData;
+pub use text_format::{to_redacted_text, Compact, TextFormat};
+pub use unknown_fields::{UnknownField, UnknownFieldSet};
+pub use well_known_types::{
+    BoolValue, BytesValue, DoubleValue, Duration, DurationError, Empty, FieldMask, FieldMaskTarget,
+    FloatValue, Int32Value, Int64Value, StringValue, Struct, Timestamp, TimestampError,
+    UInt32Value, UInt64Value, Value,
+};
+// Low-level wire-format building blocks, for tooling (splitters, samplers)
+// that needs to do its own wire-format math instead of going through a
+// generated message's `serialize`/`parse`.
+pub use wire::{decode_tag, decode_varint, decode_zigzag, encode_tag, encode_varint, encode_zigzag, Tag, WireType};
+
+/// The types and traits most call sites working with generated messages
+/// need, in one `use protobuf::prelude::*` instead of a handful of
+/// separate lines.
+///
+/// There's no `ViewProxy`/`MutProxy`/`Proxied`/`SettableValue` to export
+/// here the way upb's proxy-based generic code needs: as `message.rs`'s
+/// module doc explains, this crate's fields are plain owned values with
+/// no arena-backed view to proxy, so generic code over a field just
+/// names the field's own type (or bounds on [`Enum`]/[`MapKey`] for the
+/// cases that need a trait at all) rather than a `Proxied::View`
+/// associated type. This module re-exports what fills that role here
+/// instead: [`Message`] and [`CopyFrom`] for whole messages, [`Enum`]
+/// for generated enums, and [`Repeated`]/[`Map`]/[`Optional`]/
+/// [`PrimitiveMut`] for the field wrapper types most generated accessors
+/// return.
+pub mod prelude {
+    pub use crate::{CopyFrom, Enum, Map, MapKey, Message, Optional, PrimitiveMut, Repeated};
+}
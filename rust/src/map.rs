@@ -0,0 +1,370 @@
+//! `Map<K, V>`: protobuf's map-field wrapper, restricted at compile time
+//! to the key types the wire format actually allows.
+//!
+//! A `map<key_type, value_type>` field may only declare `key_type` as
+//! `bool`, an integral scalar (`int32`/`int64`/`uint32`/`uint64`,
+//! their `sint`/`fixed`/`sfixed` variants -- all of which already share a
+//! Rust type with the plain variant, so there's nothing extra to
+//! enumerate for them -- or `string`; never `bytes` or a floating-point
+//! type. Before this module, nothing enforced that restriction on the
+//! Rust side, so a `bytes`-keyed map surfaced whatever generic bound
+//! happened to fail first, pointing the reader at the wrong trait.
+//! [`MapKey`] is sealed so the legal set can't grow from outside this
+//! crate, and its `#[diagnostic::on_unimplemented]` message replaces
+//! rustc's default "trait not satisfied" text with the actual rule.
+
+use alloc::collections::btree_map::{self, BTreeMap};
+use alloc::string::String;
+use core::fmt;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Implemented only for `bool`, the integral scalar types, and `String`
+/// -- the key types a `.proto` map field is allowed to declare. Sealed,
+/// so callers can't extend the set; in particular `[u8]`/`Vec<u8>` (a
+/// `bytes` field) and `f32`/`f64` deliberately don't implement it.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a legal protobuf map key type",
+    note = "protobuf map keys may only be `bool`, an integral scalar (int32/int64/uint32/uint64 and \
+            their sint/fixed/sfixed variants), or `string` -- never `bytes`/`Vec<u8>` or a floating-point type"
+)]
+pub trait MapKey: private::Sealed + Ord + Clone {}
+
+macro_rules! impl_map_key {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl private::Sealed for $ty {}
+            impl MapKey for $ty {}
+        )*
+    };
+}
+
+impl_map_key!(bool, i32, i64, u32, u64, String);
+
+/// Returned by [`Map::try_insert`] when `key` was already present; the
+/// attempted `value` is handed back rather than dropped, so the caller
+/// can decide what to do with it (log it, merge it, surface it in a
+/// richer error of their own) instead of losing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OccupiedError<K, V> {
+    pub key: K,
+    pub value: V,
+}
+
+impl<K: fmt::Debug, V> fmt::Display for OccupiedError<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "key {:?} is already present in this map", self.key)
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> core::error::Error for OccupiedError<K, V> {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Map<K: MapKey, V>(BTreeMap<K, V>);
+
+impl<K: MapKey, V> Map<K, V> {
+    /// An empty `Map`. `const` so a generated message's `const_default`
+    /// can build one without running any code at startup, the same
+    /// reason `SampleMessage::const_default` needs `UnknownFieldSet::new`
+    /// to be `const`.
+    pub const fn new() -> Self {
+        Map(BTreeMap::new())
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Removes every entry. A `BTreeMap` has no spare capacity to
+    /// preserve the way `Vec::clear` does, but this is still the right
+    /// way to empty one in place -- see [`crate::message::Reusable`].
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Inserts `value` under `key`, returning the previous value for that
+    /// key if one was present -- `proto3` map semantics, where a repeated
+    /// insert overwrites rather than accumulating.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.0.insert(key, value)
+    }
+
+    /// Like `insert`, but refuses to overwrite an existing key instead of
+    /// applying `insert`'s last-write-wins `proto3` map semantics -- for
+    /// a loader that needs to treat a duplicate key in its input as an
+    /// error rather than silently keeping only the last value seen.
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<(), OccupiedError<K, V>> {
+        match self.0.entry(key) {
+            btree_map::Entry::Occupied(entry) => Err(OccupiedError { key: entry.key().clone(), value }),
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(value);
+                Ok(())
+            }
+        }
+    }
+
+    /// Looks up `key`, accepting any borrowed form of `K` -- e.g.
+    /// `Map<String, V>::get("plain &str")` -- instead of forcing the
+    /// caller to build an owned `String` just to match `K` exactly, the
+    /// same `Borrow`-based relaxation `BTreeMap::get`/`HashMap::get`
+    /// already give their own callers.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: alloc::borrow::Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.0.get(key)
+    }
+
+    /// Removes `key`, accepting any borrowed form of `K` -- see
+    /// [`Map::get`].
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: alloc::borrow::Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.0.remove(key)
+    }
+
+    /// Whether `key` is present, accepting any borrowed form of `K` --
+    /// see [`Map::get`].
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: alloc::borrow::Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.0.contains_key(key)
+    }
+
+    /// Borrows `self` for the lifetime of the returned iterator, so
+    /// mutating the map (`insert`, `remove`, `clear`, ...) while a
+    /// separate handle is still iterating it is a borrow-check error here,
+    /// not a silent race a caller needs a debug-mode generation counter to
+    /// catch the way an arena-backed `MapView` with no single owner of its
+    /// storage would need at runtime -- see
+    /// `tests/map_iteration_safety/mutating_while_iterating_is_a_borrow_error.rs`.
+    /// There's only ever one owner of the backing `BTreeMap`, and the
+    /// compiler already tracks every borrow of it, so there's no separate
+    /// runtime check to add.
+    pub fn iter(&self) -> btree_map::Iter<'_, K, V> {
+        self.0.iter()
+    }
+
+    // No `is_sorted_by`/`position` pair like [`crate::Repeated::is_sorted_by`]
+    // and [`crate::Repeated::position`]: a `BTreeMap` is sorted by key by
+    // construction, so there's no unsorted state to check for, and looking
+    // a key up by predicate instead of by value is already `contains_key`
+    // (or a linear `iter().find` when the predicate isn't an equality
+    // check on the key itself).
+
+    /// Replaces every entry with `value`'s, converted via `Into` --
+    /// `map.set(some_btree_map)` or, under the `std` feature,
+    /// `map.set(some_hash_map)` -- instead of clearing and inserting each
+    /// entry by hand. As with [`crate::Repeated::set`], there's no
+    /// separate `SettableValue` trait to implement this against; an
+    /// ordinary `impl Into<Map<K, V>>` bound already plays that role for
+    /// an owned field like this one. For an arbitrary pair iterator,
+    /// collect it into a `Map<K, V>` first (`Map::from_iter` or
+    /// `.collect()`) and pass that in.
+    pub fn set(&mut self, value: impl Into<Map<K, V>>) {
+        *self = value.into();
+    }
+}
+
+impl<K: MapKey, V> Default for Map<K, V> {
+    fn default() -> Self {
+        Map::new()
+    }
+}
+
+impl<K: MapKey, V> FromIterator<(K, V)> for Map<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Map(BTreeMap::from_iter(iter))
+    }
+}
+
+impl<K: MapKey, V> Extend<(K, V)> for Map<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+impl<K: MapKey, V> From<BTreeMap<K, V>> for Map<K, V> {
+    fn from(value: BTreeMap<K, V>) -> Self {
+        Map(value)
+    }
+}
+
+impl<K: MapKey, V> From<Map<K, V>> for BTreeMap<K, V> {
+    fn from(value: Map<K, V>) -> Self {
+        value.0
+    }
+}
+
+/// `HashMap` needs `std`, unlike `BTreeMap` which `alloc` already
+/// provides -- see this crate's `#![no_std]` note in `lib.rs`.
+#[cfg(feature = "std")]
+impl<K: MapKey + std::hash::Hash, V> From<std::collections::HashMap<K, V>> for Map<K, V> {
+    fn from(value: std::collections::HashMap<K, V>) -> Self {
+        Map(BTreeMap::from_iter(value))
+    }
+}
+
+impl<K: MapKey, V> IntoIterator for Map<K, V> {
+    type Item = (K, V);
+    type IntoIter = btree_map::IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// Splits iteration across `rayon`'s thread pool instead of walking the
+/// entries on the calling thread -- there's no separate `MapView` to
+/// implement this on (see [`crate::Repeated`]'s doc comment for why not);
+/// a `Map<K, V>` is already `Sync` whenever `K`/`V` are, so these just
+/// forward to the `BTreeMap<K, V>` impls `rayon` provides.
+#[cfg(feature = "rayon")]
+impl<K: MapKey + Send, V: Send> rayon::iter::IntoParallelIterator for Map<K, V> {
+    type Iter = <BTreeMap<K, V> as rayon::iter::IntoParallelIterator>::Iter;
+    type Item = (K, V);
+
+    fn into_par_iter(self) -> Self::Iter {
+        rayon::iter::IntoParallelIterator::into_par_iter(self.0)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<'a, K: MapKey + Sync, V: Sync> rayon::iter::IntoParallelIterator for &'a Map<K, V> {
+    type Iter = <&'a BTreeMap<K, V> as rayon::iter::IntoParallelIterator>::Iter;
+    type Item = (&'a K, &'a V);
+
+    fn into_par_iter(self) -> Self::Iter {
+        rayon::iter::IntoParallelIterator::into_par_iter(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn insert_then_get_round_trips_a_value() {
+        let mut map: Map<i32, String> = Map::new();
+        assert_eq!(map.insert(1, "one".to_string()), None);
+        assert_eq!(map.get(&1), Some(&"one".to_string()));
+    }
+
+    #[test]
+    fn re_inserting_a_key_overwrites_instead_of_accumulating() {
+        let mut map: Map<String, i32> = Map::new();
+        map.insert("count".to_string(), 1);
+        let previous = map.insert("count".to_string(), 2);
+        assert_eq!(previous, Some(1));
+        assert_eq!(map.get(&"count".to_string()), Some(&2));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn bool_keys_work_as_a_two_entry_map() {
+        let map: Map<bool, &str> = [(true, "yes"), (false, "no")].into_iter().collect();
+        assert_eq!(map.get(&true), Some(&"yes"));
+        assert_eq!(map.get(&false), Some(&"no"));
+    }
+
+    #[test]
+    fn into_iter_yields_every_inserted_pair() {
+        let map: Map<u32, u32> = [(1, 10), (2, 20)].into_iter().collect();
+        let mut pairs: alloc::vec::Vec<_> = map.into_iter().collect();
+        pairs.sort();
+        assert_eq!(pairs, alloc::vec![(1, 10), (2, 20)]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_iter_visits_every_entry() {
+        use rayon::prelude::*;
+
+        let map: Map<i32, i32> = (1..=100).map(|key| (key, key * 10)).collect();
+        let sum: i32 = (&map).into_par_iter().map(|(_key, value)| value).sum();
+        assert_eq!(sum, 50500);
+        assert_eq!(map.into_par_iter().count(), 100);
+    }
+
+    #[test]
+    fn map_is_send_and_sync_when_its_key_and_value_are() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Map<alloc::string::String, i32>>();
+    }
+
+    #[test]
+    fn get_accepts_a_borrowed_str_for_a_string_keyed_map() {
+        let mut map: Map<String, i32> = Map::new();
+        map.insert("one".to_string(), 1);
+
+        assert_eq!(map.get("one"), Some(&1));
+        assert!(map.contains_key("one"));
+    }
+
+    #[test]
+    fn remove_accepts_a_borrowed_str_for_a_string_keyed_map() {
+        let mut map: Map<String, i32> = Map::new();
+        map.insert("one".to_string(), 1);
+
+        assert_eq!(map.remove("one"), Some(1));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn try_insert_succeeds_for_a_fresh_key() {
+        let mut map: Map<i32, &str> = Map::new();
+        assert_eq!(map.try_insert(1, "one"), Ok(()));
+        assert_eq!(map.get(&1), Some(&"one"));
+    }
+
+    #[test]
+    fn try_insert_rejects_a_duplicate_key_and_hands_the_value_back() {
+        let mut map: Map<i32, &str> = Map::new();
+        map.insert(1, "one");
+        assert_eq!(map.try_insert(1, "uno"), Err(OccupiedError { key: 1, value: "uno" }));
+        // The original value is untouched.
+        assert_eq!(map.get(&1), Some(&"one"));
+    }
+
+    #[test]
+    fn set_replaces_existing_entries_from_a_btree_map() {
+        let mut map: Map<i32, i32> = [(1, 10)].into_iter().collect();
+        let replacement: BTreeMap<i32, i32> = [(2, 20), (3, 30)].into_iter().collect();
+        map.set(replacement);
+        assert_eq!(map.get(&1), None);
+        assert_eq!(map.get(&2), Some(&20));
+        assert_eq!(map.get(&3), Some(&30));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn set_replaces_existing_entries_from_a_hash_map() {
+        let mut map: Map<String, i32> = [("old".to_string(), 1)].into_iter().collect();
+        let mut replacement = std::collections::HashMap::new();
+        replacement.insert("new".to_string(), 2);
+        map.set(replacement);
+        assert_eq!(map.get(&"old".to_string()), None);
+        assert_eq!(map.get(&"new".to_string()), Some(&2));
+    }
+
+    #[test]
+    fn extend_adds_entries_without_disturbing_existing_ones() {
+        let mut map: Map<i32, i32> = [(1, 10)].into_iter().collect();
+        map.extend([(2, 20)]);
+        assert_eq!(map.get(&1), Some(&10));
+        assert_eq!(map.get(&2), Some(&20));
+    }
+}
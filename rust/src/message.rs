@@ -0,0 +1,587 @@
+//! The `Message` trait implemented by every generated message type.
+//!
+//! Note: there's no C++ kernel here to bridge a `Msg::from_cpp_ptr`/
+//! `as_cpp_ptr` pair to. Every generated message in this crate owns its
+//! fields directly as plain Rust values (see [`Message`]'s doc comment),
+//! not as a view over a `::google::protobuf::Message*` living in a C++
+//! arena, so there's no existing C++-owned message to view without a
+//! copy -- the wire-format round trip (`TryFrom<&[u8]>`/`serialize`) is
+//! the only boundary this crate has to cross.
+//!
+//! Note: there's no `Proxied`/`ViewProxy`/`MutProxy` split to give owned
+//! messages a uniform `as_view()`/`as_mut()` entry point into, for the
+//! same reason [`crate::Repeated`]'s doc comment gives for not needing a
+//! `RepeatedMut`/`RepeatedView` split -- that machinery exists upstream to
+//! distinguish a proxy into arena-owned storage (borrowed through a
+//! `View<'msg>`/`Mut<'msg>` associated type) from the owned message
+//! itself, and every message here already owns its fields directly with
+//! no arena underneath. A generic function written against "a view of
+//! `M`" or "a mutable view of `M`" in this crate already just takes
+//! `&M`/`&mut M` with an `M: Message` bound -- ordinary Rust references
+//! play the role `as_view()`/`as_mut()` calls would, uniformly across
+//! every generated message, with no conversion call needed at the call
+//! site because there's nothing to convert from.
+//!
+//! Note: relatedly, there's no `*_view(&self) -> View<'msg, [u8]>` split
+//! between a `bytes`/`string` accessor's return lifetime and the
+//! message borrow's own lifetime. That split exists upstream to let a
+//! view outlive the specific `&self` call that produced it -- tied
+//! instead to `'msg`, the arena's lifetime -- so a caller can hold one
+//! across later non-mutating calls on the same message without the
+//! borrow checker treating those calls as conflicting borrows. Every
+//! `bytes`/`string` field here is a plain owned `Vec<u8>`/`String` (see
+//! `cord.rs`'s doc comment on why there's no `ctype=CORD` backing to
+//! bridge either), so an accessor already returns `&'a Vec<u8>`/`&'a
+//! str` borrowed directly from `&'a self` -- the most precise lifetime
+//! obtainable without unsafely widening it past the borrow that
+//! actually backs the data. Two non-mutating accessor calls on the same
+//! message already coexist under ordinary borrow-checker rules (`&self`
+//! doesn't conflict with `&self`); what the upstream split additionally
+//! buys is surviving a later *mutating* call, which isn't sound here:
+//! without an arena to hand out a longer-lived, independently-tracked
+//! view into, an accessor's returned reference must end when a `&mut
+//! self` call needs exclusive access to the same field.
+
+use alloc::boxed::Box;
+use core::fmt;
+
+use crate::unknown_fields::UnknownFieldSet;
+
+/// Why `TryFrom<&[u8]>`/`parse` failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input didn't contain a valid wire-format encoding of the target
+    /// message (e.g. a length-delimited field's length ran past the end of
+    /// the buffer).
+    Malformed,
+    /// The input was larger than the `ParseOptions::max_message_size` it
+    /// was parsed with.
+    SizeLimitExceeded,
+    /// The input parsed into a well-formed message, but a [`Validator`]
+    /// run by [`parse_validated`] rejected it.
+    ValidationFailed,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Malformed => write!(f, "truncated or malformed protobuf wire-format encoding"),
+            ParseError::SizeLimitExceeded => write!(f, "message exceeded the configured max_message_size"),
+            ParseError::ValidationFailed => write!(f, "message failed validation"),
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// Options governing how a message is parsed.
+///
+/// `max_message_size` is this crate's equivalent of upb's arena-size cap:
+/// without a limit, a malicious oversized or deeply-nested payload can
+/// grow memory use unboundedly before a generated message type gets a
+/// chance to validate it. The default has no limit, matching `parse`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseOptions {
+    max_message_size: Option<usize>,
+    validate: bool,
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rejects input larger than `bytes` with
+    /// `ParseError::SizeLimitExceeded` instead of parsing it.
+    pub fn max_message_size(mut self, bytes: usize) -> Self {
+        self.max_message_size = Some(bytes);
+        self
+    }
+
+    /// Whether [`parse_validated`] should run its `Validator` at all.
+    /// Off by default, since most callers parsing trusted input (a
+    /// message this process just serialized itself) have no validator to
+    /// run and shouldn't pay for the check.
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    /// Checks `len` against the configured limit, if any. Generated
+    /// message `parse` methods call this on the input buffer before doing
+    /// any work.
+    pub fn check_len(&self, len: usize) -> Result<(), ParseError> {
+        match self.max_message_size {
+            Some(limit) if len > limit => Err(ParseError::SizeLimitExceeded),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether `validate(true)` was set. Named apart from `validate`
+    /// itself so the builder setter and this getter don't collide.
+    pub fn should_validate(&self) -> bool {
+        self.validate
+    }
+}
+
+/// Implemented by every generated message type.
+///
+/// Beyond field accessors (which the plugin emits per-message, since their
+/// shape depends on the `.proto` schema), every message exposes the same
+/// unknown-field handling so generic code can inspect or strip unknown data
+/// without knowing the concrete message type.
+///
+/// Owned messages in this crate hold their fields directly (`String`,
+/// `Vec<u8>`, nested owned messages, ...) rather than borrowing from a
+/// shared arena, so every generated message is `Send + Sync` as long as its
+/// field types are. Generated code should not need to do anything special
+/// to get this; it's asserted per-message in that message's own tests --
+/// and the same goes for every field-wrapper type in this crate
+/// ([`crate::Optional`], [`crate::Repeated`], [`crate::Map`],
+/// [`crate::PrimitiveMut`], [`crate::UnknownFieldSet`], [`crate::Cord`]),
+/// each of which carries its own `assert_send_sync::<T>()` test alongside
+/// its other tests rather than one central audit test here, so a future
+/// field-wrapper type that somehow ends up `!Send`/`!Sync` (e.g. by
+/// smuggling in a `Cell`/`Rc`) fails in the same file that introduced it.
+pub trait Message {
+    /// The fields present on the wire that this message's schema doesn't
+    /// declare. Empty unless the message was produced by parsing bytes
+    /// that contained such fields.
+    fn unknown_fields(&self) -> &UnknownFieldSet;
+
+    /// Mutable access to the same set -- needed by
+    /// [`crate::extensions::MessageExt`]'s `set_extension`/
+    /// `clear_extension`, since a proto2 extension value is stored as an
+    /// unknown field until a caller with the matching `ExtensionId` reads
+    /// or writes it.
+    fn unknown_fields_mut(&mut self) -> &mut UnknownFieldSet;
+
+    /// Discards all unknown fields. Serializing afterwards will not emit
+    /// them even if the message was originally parsed with some.
+    fn clear_unknown_fields(&mut self);
+
+    /// Clones `self`, applies `f` to the clone, and returns it -- for
+    /// treating messages as immutable values (building a new one from an
+    /// old one plus a change) instead of mutating in place. `self` is
+    /// left untouched; the clone `f` mutates is the only copy that ever
+    /// sees the change. Requires `Self: Clone`, which every generated
+    /// message in this crate already derives.
+    fn modified<F>(&self, f: F) -> Self
+    where
+        Self: Sized + Clone,
+        F: FnOnce(&mut Self),
+    {
+        let mut clone = self.clone();
+        f(&mut clone);
+        clone
+    }
+}
+
+/// Gives any field value a `copy_from` method, so generated code can write
+/// `dst.field_mut().copy_from(src.field())` to copy just one field's value
+/// between two different messages without serializing either one.
+///
+/// Upstream's `upb_Message_DeepCopy` exists because a message-typed field
+/// there is a `upb_Message*` into an arena, and copying one across arenas
+/// means walking its `MiniTable` to deep-copy every submessage it points
+/// to. This crate's message-typed fields are plain owned values (see this
+/// module's doc comment), so there's no separate arena to walk and no
+/// pointer to rebind -- copying one field's value into another is already
+/// exactly what `Clone` does. `CopyFrom` is blanket-implemented for every
+/// `Clone` type so that idiom reads the same at a oneof submessage field
+/// (`dst.nested_message_mut().copy_from(src.nested_message().unwrap())`)
+/// as it would at a scalar one.
+pub trait CopyFrom {
+    /// Overwrites `self` with a copy of `src`'s value.
+    fn copy_from(&mut self, src: &Self);
+}
+
+impl<T: Clone> CopyFrom for T {
+    fn copy_from(&mut self, src: &Self) {
+        self.clone_from(src);
+    }
+}
+
+/// A streaming sink for a message's serialized bytes, for hashing a
+/// message too large to comfortably serialize into one `Vec<u8>` first.
+/// Mirrors the `update(&mut self, bytes: &[u8])` shape most hasher crates
+/// (`sha2`, `crc32fast`, the `digest` crate family, ...) already expose,
+/// so wiring one in means implementing this trait for a thin wrapper
+/// around it, not adapting an API.
+///
+/// This is a type parameter a caller supplies to a generated message's
+/// `digest_into` method, not a crate-selected hashing algorithm -- the
+/// same reasoning [`Validator`]'s doc comment gives for not baking a
+/// particular validation library into [`parse_validated`].
+pub trait Digest {
+    /// Feeds `bytes` into the running digest.
+    fn update(&mut self, bytes: &[u8]);
+}
+
+/// Implemented by message types that can be reset to their default value
+/// in place, keeping their current heap allocations (a `String`/`Vec<u8>`
+/// field's buffer, a submessage field's own allocations) intact for the
+/// next use rather than dropping them -- what [`crate::MessagePool`] needs
+/// to hand back a "fresh" message without paying to reallocate it.
+///
+/// Not every default-constructible message needs its own hand-written
+/// impl to benefit: a derived `Default` plus `*self = Self::default()`
+/// would satisfy the same signature, but would also free every buffer
+/// and reallocate it on the next use, defeating the point of pooling.
+pub trait Reusable: Default {
+    /// Resets `self` to its default value, preferring to reuse existing
+    /// allocations over dropping and reallocating them.
+    fn clear(&mut self);
+}
+
+/// Leaks `message` into a `&'static M`, for process-lifetime values (e.g. a
+/// config proto parsed once at startup and handed out to many threads)
+/// that would otherwise need a `OnceLock`/`Lazy` wrapper at every call
+/// site. There's no arena to keep alive here: as this module's doc comment
+/// notes, generated messages in this crate own their fields directly
+/// rather than borrowing from one, so `leak` is just `Box::leak` under a
+/// name that documents the intent. The usual caveat applies -- the memory
+/// is never freed, so only call this for values meant to live for the
+/// rest of the process.
+pub fn leak<M: Message>(message: M) -> &'static M {
+    Box::leak(Box::new(message))
+}
+
+/// Parses `bytes` as `M`, for generic callers (a cache keyed by message
+/// type, an RPC dispatcher) that only know `M` as a type parameter and
+/// don't want to spell out `M::try_from`/`M::parse` at every call site.
+/// Just `M::try_from` under a name that reads the same regardless of
+/// which generated type `M` is.
+pub fn parse<M>(bytes: &[u8]) -> Result<M, ParseError>
+where
+    M: for<'a> TryFrom<&'a [u8], Error = ParseError>,
+{
+    M::try_from(bytes)
+}
+
+/// Parses `M` from `slices` concatenated in order, for a caller (a
+/// network stack whose payload landed across several ring-buffer
+/// segments) that has the bytes split across multiple buffers instead of
+/// one contiguous one.
+///
+/// This still copies every segment into one buffer before parsing: the
+/// wire-format decoder throughout this crate (`decode_varint`,
+/// length-delimited sub-slicing in every generated `try_parse_fields`)
+/// assumes a single contiguous `&[u8]`, the same as `parse`'s own
+/// `TryFrom<&[u8]>` bound, and teaching it to walk a slice-of-slices
+/// cursor instead would mean reworking every generated message's parse
+/// code, not adding a function here. What this does avoid is a *second*
+/// copy: a caller that would otherwise flatten the segments into a
+/// `Vec<u8>` itself before calling `parse` can hand the segments
+/// straight in and let this size the buffer once, up front, instead of
+/// reallocating as it grows.
+pub fn parse_from_slices<M>(slices: &[&[u8]]) -> Result<M, ParseError>
+where
+    M: for<'a> TryFrom<&'a [u8], Error = ParseError>,
+{
+    let total_len: usize = slices.iter().map(|slice| slice.len()).sum();
+    let mut buf = alloc::vec::Vec::with_capacity(total_len);
+    for slice in slices {
+        buf.extend_from_slice(slice);
+    }
+    parse(&buf)
+}
+
+/// Serializes `message`, for generic callers that only know it as `&M`.
+/// Just `Vec::from` under a name that doesn't require the caller to
+/// remember which direction that `From` impl runs.
+pub fn serialize<M>(message: &M) -> alloc::vec::Vec<u8>
+where
+    alloc::vec::Vec<u8>: for<'a> From<&'a M>,
+{
+    alloc::vec::Vec::from(message)
+}
+
+/// Reports parse/serialize calls [`parse_instrumented`]/
+/// [`serialize_instrumented`] make, behind the `metrics` feature.
+///
+/// A caller-supplied hook rather than a process-global registry: this
+/// crate has no home for global mutable state (see `arena.rs`'s doc
+/// comment on why there's no process-wide scratch table for a C++/upb
+/// kernel-style registry to live in either), so exporting hot-spot
+/// metrics means wrapping the call at a boundary that already knows
+/// which recorder to report to -- the same reasoning [`Validator`]'s doc
+/// comment gives for taking a validator as a parameter instead of
+/// consulting a registry.
+#[cfg(feature = "metrics")]
+pub trait Recorder {
+    /// Reports one `parse` call: `message_type` is `M`'s
+    /// `core::any::type_name`, `bytes` the size of the input it parsed,
+    /// `duration` how long the call took.
+    fn record_parse(&self, message_type: &'static str, bytes: usize, duration: std::time::Duration);
+
+    /// Reports one `serialize` call: `bytes` is the size of the output it
+    /// produced.
+    fn record_serialize(&self, message_type: &'static str, bytes: usize, duration: std::time::Duration);
+}
+
+/// Like [`parse`], but times the call and reports it to `recorder`.
+#[cfg(feature = "metrics")]
+pub fn parse_instrumented<M>(bytes: &[u8], recorder: &impl Recorder) -> Result<M, ParseError>
+where
+    M: for<'a> TryFrom<&'a [u8], Error = ParseError>,
+{
+    let start = std::time::Instant::now();
+    let result = M::try_from(bytes);
+    recorder.record_parse(core::any::type_name::<M>(), bytes.len(), start.elapsed());
+    result
+}
+
+/// Like [`serialize`], but times the call and reports it to `recorder`.
+#[cfg(feature = "metrics")]
+pub fn serialize_instrumented<M>(message: &M, recorder: &impl Recorder) -> alloc::vec::Vec<u8>
+where
+    alloc::vec::Vec<u8>: for<'a> From<&'a M>,
+{
+    let start = std::time::Instant::now();
+    let bytes = serialize(message);
+    recorder.record_serialize(core::any::type_name::<M>(), bytes.len(), start.elapsed());
+    bytes
+}
+
+/// Why a [`Validator`] rejected a message. Carries the reason as a
+/// caller-supplied message rather than a closed set of variants, since
+/// unlike `ParseError` (which enumerates this crate's own, fixed set of
+/// wire-format failure modes) a validator's constraints are arbitrary and
+/// user-defined -- a string length bound, a required-field check, a
+/// cross-field invariant -- and this crate has no schema for *those* to
+/// enumerate against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError(pub alloc::string::String);
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl core::error::Error for ValidationError {}
+
+/// User-supplied per-message validation, run by [`parse_validated`] when
+/// its `ParseOptions` has `validate(true)` set.
+///
+/// This is a type parameter, not a registry generated code consults
+/// automatically: there's nowhere in this crate to stash a process-wide
+/// hook table (see `arena.rs`'s doc comment on why there's no
+/// process-global scratch state here either), so the hook a caller wants
+/// to run is the one it passes to `parse_validated` -- a protoc-gen-validate
+/// style checker derived from schema options would plug in the same way a
+/// hand-written one does.
+pub trait Validator<M> {
+    /// Checks `message`, e.g. a string-length or required-field
+    /// constraint this crate's schema alone doesn't express.
+    fn validate(&self, message: &M) -> Result<(), ValidationError>;
+}
+
+/// Parses `bytes` as `M`, then runs `validator` against it when
+/// `options.should_validate()` is set -- the hook point
+/// `ParseOptions::validate(true)` exists to gate.
+pub fn parse_validated<M, V>(bytes: &[u8], options: &ParseOptions, validator: &V) -> Result<M, ParseError>
+where
+    M: for<'a> TryFrom<&'a [u8], Error = ParseError>,
+    V: Validator<M>,
+{
+    let message = M::try_from(bytes)?;
+    if options.should_validate() {
+        validator.validate(&message).map_err(|_| ParseError::ValidationFailed)?;
+    }
+    Ok(message)
+}
+
+/// Re-encodes `old` and parses the result as `New`, for rolling a message
+/// from one generated type to another across a schema change (a service
+/// upgrading `FooV1` to `FooV2` mid-rollout, while some peers still send
+/// the old shape). There's no reflection-based field-by-field copy here:
+/// this crate's generated messages don't expose enough shared structure
+/// for one to walk generically (see `reflect.rs`'s doc comment on what
+/// its reflection surface doesn't cover yet), so the serialize/parse round
+/// trip *is* the conversion. That round trip is also why this preserves
+/// data a field-by-field copy would drop: any field `Old` doesn't
+/// recognize as declared ends up in its `unknown_fields`, gets
+/// re-serialized by `serialize()` same as a declared field would, and
+/// `New` either recognizes it (if the schema gained that field) or parks
+/// it in its own `unknown_fields` in turn.
+pub fn transcode<Old, New>(old: &Old) -> Result<New, ParseError>
+where
+    alloc::vec::Vec<u8>: for<'a> From<&'a Old>,
+    New: for<'a> TryFrom<&'a [u8], Error = ParseError>,
+{
+    New::try_from(&serialize(old))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample_gen::SampleMessage;
+
+    #[test]
+    fn leak_returns_a_static_reference_to_the_given_value() {
+        let message: &'static SampleMessage = leak(SampleMessage::new("bob"));
+        assert_eq!(message.name, "bob");
+    }
+
+    #[test]
+    fn parse_and_serialize_round_trip_generically_over_the_message_type() {
+        let original = SampleMessage::new("carol");
+        let wire = serialize(&original);
+        let decoded: SampleMessage = parse(&wire).unwrap();
+        assert_eq!(decoded, original);
+    }
+
+    #[test]
+    fn parse_from_slices_parses_a_payload_split_across_several_buffers() {
+        let original = SampleMessage::new("carol");
+        let wire = serialize(&original);
+        let (first, second) = wire.split_at(wire.len() / 2);
+        let decoded: SampleMessage = parse_from_slices(&[first, second]).unwrap();
+        assert_eq!(decoded, original);
+    }
+
+    #[test]
+    fn parse_from_slices_matches_parse_on_a_single_slice() {
+        let original = SampleMessage::new("dave");
+        let wire = serialize(&original);
+        let decoded: SampleMessage = parse_from_slices(&[&wire]).unwrap();
+        assert_eq!(decoded, original);
+    }
+
+    #[test]
+    fn modified_leaves_the_original_untouched_and_returns_a_changed_clone() {
+        let original = SampleMessage::new("erin");
+        let changed = original.modified(|m| m.name = "frank".to_string());
+
+        assert_eq!(original.name, "erin");
+        assert_eq!(changed.name, "frank");
+    }
+
+    #[test]
+    fn parse_validated_skips_the_validator_when_validate_is_off() {
+        struct RejectEverything;
+        impl Validator<SampleMessage> for RejectEverything {
+            fn validate(&self, _message: &SampleMessage) -> Result<(), ValidationError> {
+                Err(ValidationError("rejected".into()))
+            }
+        }
+
+        let wire = serialize(&SampleMessage::new("heidi"));
+        let options = ParseOptions::new();
+        let decoded: SampleMessage = parse_validated(&wire, &options, &RejectEverything).unwrap();
+        assert_eq!(decoded.name, "heidi");
+    }
+
+    #[test]
+    fn parse_validated_runs_the_validator_when_validate_is_on() {
+        struct NonEmptyName;
+        impl Validator<SampleMessage> for NonEmptyName {
+            fn validate(&self, message: &SampleMessage) -> Result<(), ValidationError> {
+                if message.name.is_empty() {
+                    Err(ValidationError("name must not be empty".into()))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+
+        let options = ParseOptions::new().validate(true);
+
+        let ok_wire = serialize(&SampleMessage::new("ivan"));
+        let decoded: SampleMessage = parse_validated(&ok_wire, &options, &NonEmptyName).unwrap();
+        assert_eq!(decoded.name, "ivan");
+
+        let empty_wire = serialize(&SampleMessage::new(""));
+        let result: Result<SampleMessage, ParseError> = parse_validated(&empty_wire, &options, &NonEmptyName);
+        assert_eq!(result, Err(ParseError::ValidationFailed));
+    }
+
+    #[test]
+    fn copy_from_copies_a_submessage_field_between_two_different_messages() {
+        use crate::sample_gen::ResultGroup;
+
+        let mut src = SampleMessage::new("source");
+        src.result_group_mut().legacy_code = 42;
+
+        let mut dst = SampleMessage::new("dest");
+        dst.result_group_mut().copy_from(src.result_group().unwrap());
+
+        assert_eq!(dst.result_group().unwrap().legacy_code, 42);
+        // Only the field was copied, not the whole message.
+        assert_eq!(dst.name, "dest");
+
+        // The copy doesn't alias `src`'s field -- mutating one leaves the
+        // other untouched.
+        src.result_group_mut().legacy_code = 7;
+        assert_eq!(dst.result_group().unwrap().legacy_code, 42);
+
+        let mut default_group = ResultGroup::default();
+        default_group.copy_from(src.result_group().unwrap());
+        assert_eq!(default_group.legacy_code, 7);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn parse_and_serialize_instrumented_report_to_the_recorder() {
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct CountingRecorder {
+            parses: Mutex<Vec<(&'static str, usize)>>,
+            serializes: Mutex<Vec<(&'static str, usize)>>,
+        }
+
+        impl Recorder for CountingRecorder {
+            fn record_parse(&self, message_type: &'static str, bytes: usize, _duration: std::time::Duration) {
+                self.parses.lock().unwrap().push((message_type, bytes));
+            }
+
+            fn record_serialize(&self, message_type: &'static str, bytes: usize, _duration: std::time::Duration) {
+                self.serializes.lock().unwrap().push((message_type, bytes));
+            }
+        }
+
+        let recorder = CountingRecorder::default();
+        let original = SampleMessage::new("judy");
+
+        let wire = serialize_instrumented(&original, &recorder);
+        let decoded: SampleMessage = parse_instrumented(&wire, &recorder).unwrap();
+        assert_eq!(decoded, original);
+
+        let serializes = recorder.serializes.lock().unwrap();
+        assert_eq!(serializes.len(), 1);
+        assert_eq!(serializes[0].0, core::any::type_name::<SampleMessage>());
+        assert_eq!(serializes[0].1, wire.len());
+
+        let parses = recorder.parses.lock().unwrap();
+        assert_eq!(parses.len(), 1);
+        assert_eq!(parses[0], (core::any::type_name::<SampleMessage>(), wire.len()));
+    }
+
+    #[test]
+    fn transcode_preserves_fields_the_new_type_does_not_declare() {
+        use crate::wire::{encode_tag, encode_varint, WireType};
+
+        // This crate has only one generated message type in this snapshot
+        // (see `sample_gen.rs`'s doc comment), so `Old` and `New` here are
+        // both `SampleMessage` -- standing in for two different schema
+        // versions of what is, on the wire, the same message shape. A
+        // real field number `SampleMessage` doesn't declare plays the
+        // part of a field the old schema doesn't know about yet.
+        let mut wire = alloc::vec::Vec::new();
+        encode_varint(encode_tag(1, WireType::LengthDelimited), &mut wire);
+        encode_varint(4, &mut wire);
+        wire.extend_from_slice(b"dave");
+        encode_varint(encode_tag(99, WireType::Varint), &mut wire);
+        encode_varint(7, &mut wire);
+
+        let old = SampleMessage::parse(&wire);
+        let new: SampleMessage = transcode(&old).unwrap();
+
+        assert_eq!(new.name, "dave");
+        assert_eq!(new.unknown_fields(), old.unknown_fields());
+        assert!(serialize(&new).ends_with(&wire[wire.len() - 2..]));
+    }
+}
@@ -0,0 +1,124 @@
+//! `Optional<T>`: protobuf's explicit-presence field wrapper.
+//!
+//! Generated fields that track "was this ever set" separately from "what's
+//! its current value" (a proto3 `optional` scalar, or any
+//! [`crate::well_known_types`] wrapper-message field) could just as well
+//! be plain `Option<T>`. `Optional` exists anyway so the crate can hang
+//! presence-specific helpers (`is_set`, `unwrap_or_default`) off a type it
+//! owns, without adding inherent impls to `Option` itself -- and so moving
+//! a value in or out of `std::Option` is an explicit `From`/`Into` hop
+//! rather than two names aliasing the same type.
+//!
+//! There's no `PresentField`/`AbsentField` split here -- this crate has
+//! one `Optional<T>` for both states, not a pair of zero-sized marker
+//! types a vtable-backed proxy would need -- but it gets the same
+//! motivating benefit: `Optional`'s `Debug` impl below spells out
+//! "set"/"unset" instead of deriving straight through to `Option`'s
+//! `Some`/`None`, so a failing assertion reads as presence, not as an
+//! `Option` the reader has to translate back into proto3 semantics.
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Optional<T>(Option<T>);
+
+/// Spells out presence instead of relying on the derived `Some`/`None`
+/// rendering, so a failing test's assertion or a log line reads
+/// "unset"/"set" rather than making the reader map `Option`'s proto3
+/// meaning back on top of it.
+impl<T: core::fmt::Debug> core::fmt::Debug for Optional<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self.0 {
+            Some(value) => f.debug_tuple("Optional::set").field(value).finish(),
+            None => f.write_str("Optional::unset"),
+        }
+    }
+}
+
+impl<T> Optional<T> {
+    /// An `Optional` with no value set.
+    pub fn unset() -> Self {
+        Optional(None)
+    }
+
+    /// An `Optional` holding `value`.
+    pub fn set(value: T) -> Self {
+        Optional(Some(value))
+    }
+
+    /// Whether a value is present.
+    pub fn is_set(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Borrows the value, if present.
+    pub fn as_ref(&self) -> Option<&T> {
+        self.0.as_ref()
+    }
+
+    /// Returns the value, or `T::default()` if unset -- the proto3 rule
+    /// for reading a field that was never assigned.
+    pub fn unwrap_or_default(self) -> T
+    where
+        T: Default,
+    {
+        self.0.unwrap_or_default()
+    }
+}
+
+impl<T> Default for Optional<T> {
+    fn default() -> Self {
+        Optional::unset()
+    }
+}
+
+impl<T> From<Option<T>> for Optional<T> {
+    fn from(value: Option<T>) -> Self {
+        Optional(value)
+    }
+}
+
+impl<T> From<Optional<T>> for Option<T> {
+    fn from(value: Optional<T>) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_set_reflects_presence() {
+        assert!(!Optional::<i32>::unset().is_set());
+        assert!(Optional::set(5).is_set());
+    }
+
+    #[test]
+    fn converts_to_and_from_option() {
+        let optional: Optional<i32> = Some(7).into();
+        assert_eq!(optional, Optional::set(7));
+        let back: Option<i32> = optional.into();
+        assert_eq!(back, Some(7));
+
+        let optional: Optional<i32> = None.into();
+        let back: Option<i32> = optional.into();
+        assert_eq!(back, None);
+    }
+
+    #[test]
+    fn unwrap_or_default_synthesizes_the_default_when_unset() {
+        assert_eq!(Optional::<i32>::unset().unwrap_or_default(), 0);
+        assert_eq!(Optional::set(5).unwrap_or_default(), 5);
+    }
+
+    #[test]
+    fn debug_spells_out_presence_instead_of_option_s_some_none() {
+        assert_eq!(alloc::format!("{:?}", Optional::<i32>::unset()), "Optional::unset");
+        assert_eq!(alloc::format!("{:?}", Optional::set(5)), "Optional::set(5)");
+    }
+
+    #[test]
+    fn optional_is_send_and_sync_when_its_value_is() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Optional<i32>>();
+    }
+}
@@ -0,0 +1,151 @@
+//! `MessagePool<M>`: a free list of cleared messages, for amortizing a
+//! message's internal allocations (a `String`/`Vec<u8>` field's buffer,
+//! ...) across many request/response cycles instead of letting them drop
+//! and reallocate every time.
+//!
+//! There's no arena for a pool to hand out reused *arenas* backing the
+//! way the request body describes upstream's version of this (see
+//! `arena.rs`'s doc comment on why there's no arena under a message's
+//! fields here at all) -- this crate's messages own their fields
+//! directly, so the thing actually worth reusing across requests is each
+//! field's own heap allocation. [`MessagePool::get`] hands one out via
+//! [`PooledMessage`], a guard that puts the message back on the pool's
+//! free list -- cleared with [`crate::message::Reusable::clear`], which
+//! keeps those allocations rather than dropping them -- when it's
+//! dropped.
+//!
+//! Single-threaded, the same way [`crate::Arena`] is: a `RefCell`-backed
+//! free list is scratch space for one thread's request loop, not shared
+//! state a `Mutex` would need to protect across a thread pool. A
+//! multi-threaded server pools one `MessagePool` per worker thread, the
+//! same way it would pool one `Arena` per worker in the C++/upb kernels.
+
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::ops::{Deref, DerefMut};
+
+use crate::message::Reusable;
+
+/// Hands out cleared `M` values backed by a free list of previously
+/// returned ones.
+#[derive(Debug)]
+pub struct MessagePool<M> {
+    free: RefCell<Vec<M>>,
+}
+
+impl<M: Reusable> MessagePool<M> {
+    /// An empty pool. The first few `get()` calls build fresh messages
+    /// the ordinary way; the pool only starts saving allocations once
+    /// some of those are returned.
+    pub fn new() -> Self {
+        MessagePool { free: RefCell::new(Vec::new()) }
+    }
+
+    /// Hands out a cleared `M`: one reused from the free list if it has
+    /// any, or `M::default()` otherwise. Returned as a [`PooledMessage`]
+    /// guard that pushes the message back onto the free list when
+    /// dropped, instead of letting its allocations go.
+    pub fn get(&self) -> PooledMessage<'_, M> {
+        let message = self.free.borrow_mut().pop().unwrap_or_default();
+        PooledMessage { pool: self, message: Some(message) }
+    }
+
+    /// How many cleared messages are currently sitting in the free list,
+    /// ready to be handed out without allocating.
+    pub fn len(&self) -> usize {
+        self.free.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.free.borrow().is_empty()
+    }
+}
+
+impl<M: Reusable> Default for MessagePool<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A message on loan from a [`MessagePool`], returned to it automatically
+/// on drop.
+pub struct PooledMessage<'a, M: Reusable> {
+    pool: &'a MessagePool<M>,
+    // `None` only while `drop` is moving the message back onto the pool;
+    // always `Some` for any caller-visible borrow of `self`.
+    message: Option<M>,
+}
+
+impl<M: Reusable> Deref for PooledMessage<'_, M> {
+    type Target = M;
+
+    fn deref(&self) -> &M {
+        self.message.as_ref().expect("message is only None during drop")
+    }
+}
+
+impl<M: Reusable> DerefMut for PooledMessage<'_, M> {
+    fn deref_mut(&mut self) -> &mut M {
+        self.message.as_mut().expect("message is only None during drop")
+    }
+}
+
+impl<M: Reusable> Drop for PooledMessage<'_, M> {
+    fn drop(&mut self) {
+        if let Some(mut message) = self.message.take() {
+            message.clear();
+            self.pool.free.borrow_mut().push(message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample_gen::SampleMessage;
+
+    #[test]
+    fn get_reuses_a_returned_message_instead_of_leaving_the_pool_empty() {
+        let pool: MessagePool<SampleMessage> = MessagePool::new();
+        assert!(pool.is_empty());
+
+        {
+            let mut message = pool.get();
+            message.name.push_str("first");
+        }
+        assert_eq!(pool.len(), 1);
+
+        let message = pool.get();
+        assert_eq!(message.name, "", "clear() should have reset the reused message");
+        assert!(pool.is_empty(), "get() should take the message back out of the free list");
+    }
+
+    #[test]
+    fn clear_keeps_the_name_buffer_s_capacity_for_reuse() {
+        let pool: MessagePool<SampleMessage> = MessagePool::new();
+        {
+            let mut message = pool.get();
+            message.name.push_str("a string long enough to require a heap allocation");
+        }
+        let reused_capacity = pool.free.borrow()[0].name.capacity();
+        assert!(reused_capacity > 0);
+
+        let message = pool.get();
+        assert_eq!(message.name.capacity(), reused_capacity);
+    }
+
+    #[test]
+    fn dropping_a_pooled_message_clears_its_result_group_in_place_instead_of_unsetting_it() {
+        let pool: MessagePool<SampleMessage> = MessagePool::new();
+        {
+            let mut message = pool.get();
+            message.result_group_mut().legacy_code = 7;
+        }
+
+        // `result_group` stays set (so its own allocations are kept for
+        // reuse), but reset to its default value.
+        let message = pool.get();
+        assert!(message.has_result_group());
+        assert_eq!(message.result_group().unwrap().legacy_code, 0);
+    }
+}
@@ -0,0 +1,150 @@
+//! `PrimitiveMut<T>`: an arithmetic-friendly handle onto a mutable scalar
+//! field.
+//!
+//! Note: there's no vtable dispatch here to save -- this crate's fields
+//! are plain owned values (see `message.rs`'s doc comment), so a
+//! generated accessor like `ResultGroup::legacy_code` already hands out a
+//! bare `&mut i32` rather than a `upb_MessageValue`-backed proxy that
+//! needs a get/set round trip per update. `PrimitiveMut` exists anyway so
+//! callers updating a counter field have `add_assign`/`min_assign` to
+//! reach for instead of writing `*field = (*field).max(other)` by hand,
+//! the same reasoning [`crate::Optional`] and [`crate::Repeated`] wrap a
+//! bare `Option`/`Vec` for.
+
+use core::ops::{AddAssign, SubAssign};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct PrimitiveMut<'a, T>(&'a mut T);
+
+impl<'a, T> PrimitiveMut<'a, T> {
+    pub fn new(value: &'a mut T) -> Self {
+        PrimitiveMut(value)
+    }
+
+    pub fn get(&self) -> T
+    where
+        T: Copy,
+    {
+        *self.0
+    }
+
+    pub fn set(&mut self, value: T) {
+        *self.0 = value;
+    }
+}
+
+impl<'a, T> PrimitiveMut<'a, T>
+where
+    T: AddAssign + Copy,
+{
+    pub fn add_assign(&mut self, delta: T) {
+        *self.0 += delta;
+    }
+}
+
+impl<'a, T> PrimitiveMut<'a, T>
+where
+    T: SubAssign + Copy,
+{
+    pub fn sub_assign(&mut self, delta: T) {
+        *self.0 -= delta;
+    }
+}
+
+impl<'a, T> PrimitiveMut<'a, T>
+where
+    T: Ord + Copy,
+{
+    /// Sets the field to `other` if `other` is smaller than its current
+    /// value, otherwise leaves it unchanged.
+    pub fn min_assign(&mut self, other: T) {
+        if other < *self.0 {
+            *self.0 = other;
+        }
+    }
+
+    /// Sets the field to `other` if `other` is larger than its current
+    /// value, otherwise leaves it unchanged.
+    pub fn max_assign(&mut self, other: T) {
+        if other > *self.0 {
+            *self.0 = other;
+        }
+    }
+}
+
+impl<'a, T> From<&'a mut T> for PrimitiveMut<'a, T> {
+    fn from(value: &'a mut T) -> Self {
+        PrimitiveMut(value)
+    }
+}
+
+impl<'a, T> AddAssign<T> for PrimitiveMut<'a, T>
+where
+    T: AddAssign + Copy,
+{
+    fn add_assign(&mut self, delta: T) {
+        *self.0 += delta;
+    }
+}
+
+impl<'a, T> SubAssign<T> for PrimitiveMut<'a, T>
+where
+    T: SubAssign + Copy,
+{
+    fn sub_assign(&mut self, delta: T) {
+        *self.0 -= delta;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample_gen::SampleMessage;
+
+    #[test]
+    fn add_assign_updates_the_underlying_field_in_place() {
+        let mut message = SampleMessage::new("kate");
+        let mut counter = PrimitiveMut::new(&mut message.result_group_mut().legacy_code);
+        counter.add_assign(3);
+        counter.add_assign(4);
+        assert_eq!(message.result_group_mut().legacy_code, 7);
+    }
+
+    #[test]
+    fn sub_assign_updates_the_underlying_field_in_place() {
+        let mut message = SampleMessage::new("kate");
+        message.result_group_mut().legacy_code = 10;
+        let mut counter = PrimitiveMut::new(&mut message.result_group_mut().legacy_code);
+        counter.sub_assign(3);
+        assert_eq!(message.result_group_mut().legacy_code, 7);
+    }
+
+    #[test]
+    fn ops_add_assign_and_sub_assign_work_through_the_operator() {
+        let mut value = 5i64;
+        let mut counter = PrimitiveMut::new(&mut value);
+        counter += 2;
+        counter -= 1;
+        assert_eq!(counter.get(), 6);
+    }
+
+    #[test]
+    fn primitive_mut_is_send_and_sync_when_its_target_is() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<PrimitiveMut<'_, i32>>();
+    }
+
+    #[test]
+    fn min_assign_and_max_assign_only_move_toward_the_extreme() {
+        let mut value = 5i32;
+        let mut counter = PrimitiveMut::new(&mut value);
+        counter.max_assign(3);
+        assert_eq!(counter.get(), 5);
+        counter.max_assign(9);
+        assert_eq!(counter.get(), 9);
+        counter.min_assign(20);
+        assert_eq!(counter.get(), 9);
+        counter.min_assign(1);
+        assert_eq!(counter.get(), 1);
+    }
+}
@@ -0,0 +1,315 @@
+//! Minimal runtime reflection: descriptor metadata generated code can
+//! expose about its own message shape, for callers that need to walk
+//! fields generically (diffing, JSON/text codecs, CLI tools) without a
+//! `match` over every field.
+
+/// The subset of `FieldDescriptorProto.Type` this crate's reflection
+/// surface currently distinguishes.
+///
+/// Note: there's no `Repeated`/`Map` variant yet, even though
+/// [`crate::Repeated`]/[`crate::Map`] themselves exist and
+/// `SampleMessage::scores`/`tag_counts` (see `sample_gen.rs`) already use
+/// them -- this reflection surface just hasn't grown a way to describe
+/// those two fields generically yet, so `SampleMessage::descriptor()`
+/// leaves them out of its field list rather than describing them as some
+/// placeholder `FieldType`. That gap should close once a caller needs to
+/// walk a repeated/map field without already knowing its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    String,
+    Enum,
+}
+
+impl FieldType {
+    /// The wire type a field of this type is encoded with -- what
+    /// [`crate::FieldAccess::encoded_tag`] needs to build a field's tag
+    /// byte(s) without a per-message `match`.
+    pub fn wire_type(self) -> crate::wire::WireType {
+        match self {
+            FieldType::String => crate::wire::WireType::LengthDelimited,
+            FieldType::Enum => crate::wire::WireType::Varint,
+        }
+    }
+}
+
+/// Whether a field tracks explicit presence (a separate has-bit, the way
+/// proto2's singular fields and proto3's `optional` fields do) or
+/// implicit presence (proto3's default: "set" just means "not the
+/// field's default value", with no separate bit to check). Editions
+/// resolve this per field from the `field_presence` feature; this crate
+/// predates an editions parser (see `sample_gen`'s doc comment on why
+/// there's no `.proto` -> `.rs` pipeline here), so [`ResolvedFeatures`]
+/// reports whichever mode the generated code already implements instead
+/// of resolving it from a parsed `.proto`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldPresenceMode {
+    Explicit,
+    Implicit,
+}
+
+/// Whether an `Enum`-typed field preserves an unrecognized wire value
+/// (`Open`, proto3's default) or rejects it (`Closed`, proto2's
+/// default), resolved by editions' `enum_type` feature. `None` for a
+/// field whose [`FieldType`] isn't `Enum`, where the feature doesn't
+/// apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnumOpenness {
+    Open,
+    Closed,
+}
+
+/// Whether a `string` field's bytes are validated as UTF-8 on parse
+/// (`Verify`, proto3's default, rejecting the field on invalid UTF-8) or
+/// passed through unvalidated (`None`, a newer editions mode for ports
+/// from languages without a distinct `bytes` type). `None`-the-Rust-variant
+/// for a field whose [`FieldType`] isn't `String`, where the feature
+/// doesn't apply -- distinct from `Utf8Validation::None`-the-feature-value,
+/// unfortunately sharing the name editions gives that mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Utf8Validation {
+    Verify,
+    None,
+    /// What every `string` field here actually does: `String::from_utf8_lossy`
+    /// replaces invalid sequences with the replacement character instead
+    /// of rejecting the field (`Verify`) or keeping the raw bytes
+    /// (`None`). Neither upstream mode -- see `sample_gen`'s parsing
+    /// code for `SampleMessage::name`.
+    Lossy,
+}
+
+/// The editions feature set resolved for one field, queryable at runtime
+/// by generic middleware that needs to adapt to a field's presence/
+/// openness/validation behavior without parsing the `.proto` source --
+/// see [`FieldPresenceMode`]'s doc comment on how this crate resolves
+/// these without an editions parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolvedFeatures {
+    pub field_presence: FieldPresenceMode,
+    pub enum_type: Option<EnumOpenness>,
+    pub utf8_validation: Option<Utf8Validation>,
+}
+
+/// Static metadata about one declared field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldDescriptor {
+    pub name: &'static str,
+    pub number: u32,
+    pub field_type: FieldType,
+    /// The `debug_redact` field option: whether [`crate::TextFormat`]'s
+    /// `to_redacted_text` should print `[REDACTED]` for this field
+    /// instead of its value, for PII-bearing fields that are safe to log
+    /// the *presence* of but not the content of.
+    pub redact: bool,
+    /// The field's JSON name: the camelCase conversion of `name` by
+    /// default, or the `.proto` source's explicit `json_name` option
+    /// when the schema overrides it (some Android backend protos rely
+    /// on keeping a legacy wire field name while presenting a different
+    /// name over JSON). JSON print/parse should key on this instead of
+    /// `name` so a field round-trips through JSON under its declared
+    /// JSON name rather than its proto field name.
+    pub json_name: &'static str,
+    pub features: ResolvedFeatures,
+}
+
+/// Static metadata about one declared message, in field-declaration order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageDescriptor {
+    pub name: &'static str,
+    pub fields: &'static [FieldDescriptor],
+}
+
+impl MessageDescriptor {
+    pub fn field_by_name(&self, name: &str) -> Option<&'static FieldDescriptor> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    pub fn field_by_number(&self, number: u32) -> Option<&'static FieldDescriptor> {
+        self.fields.iter().find(|f| f.number == number)
+    }
+
+    /// Looks a field up by its JSON name (see [`FieldDescriptor::json_name`])
+    /// rather than its proto field name -- what a JSON parser needs when
+    /// reading an object key back into a field.
+    pub fn field_by_json_name(&self, json_name: &str) -> Option<&'static FieldDescriptor> {
+        self.fields.iter().find(|f| f.json_name == json_name)
+    }
+}
+
+/// Implemented by every generated message type alongside [`crate::Message`]
+/// so generic code can get at its schema, not just its unknown fields.
+pub trait Reflect {
+    fn descriptor() -> &'static MessageDescriptor;
+}
+
+/// Implemented by every generated message type so a field can be read by
+/// its declared name or number without a per-message `match`, the same way
+/// [`crate::DynamicMessage`] does for runtime-loaded schemas.
+pub trait FieldAccess: Reflect {
+    fn field(&self, number: u32) -> Option<crate::DynamicValue>;
+
+    fn field_by_name(&self, name: &str) -> Option<crate::DynamicValue> {
+        let number = Self::descriptor().field_by_name(name)?.number;
+        self.field(number)
+    }
+
+    /// The encoded tag bytes for `number` -- the same bytes `serialize()`
+    /// writes before that field's value. `None` if `number` isn't
+    /// declared, regardless of whether the field is currently set.
+    fn encoded_tag(&self, number: u32) -> Option<alloc::vec::Vec<u8>> {
+        let field = Self::descriptor().field_by_number(number)?;
+        let mut out = alloc::vec::Vec::new();
+        crate::wire::encode_varint(
+            crate::wire::encode_tag(field.number, field.field_type.wire_type()),
+            &mut out,
+        );
+        Some(out)
+    }
+
+    /// How many bytes `serialize()` would spend on this field, tag
+    /// included. `0` if `number` isn't declared, or if the field is
+    /// currently at its default value -- proto3 implicit presence omits
+    /// it from the wire entirely, so there's nothing to size. Low-level
+    /// tooling (a splitter deciding where to cut a batch, a sampler
+    /// estimating payload size) can use this to budget per field without
+    /// re-serializing the whole message just to measure one piece of it.
+    fn field_byte_size(&self, number: u32) -> usize {
+        let Some(value) = self.field(number) else { return 0 };
+        if value.is_default() {
+            return 0;
+        }
+        let tag_size = self.encoded_tag(number).map_or(0, |tag| tag.len());
+        let value_size = match &value {
+            crate::DynamicValue::String(s) => {
+                let mut len_buf = alloc::vec::Vec::new();
+                crate::wire::encode_varint(s.len() as u64, &mut len_buf);
+                len_buf.len() + s.len()
+            }
+            crate::DynamicValue::Enum(n) => {
+                let mut buf = alloc::vec::Vec::new();
+                crate::wire::encode_varint(*n as i64 as u64, &mut buf);
+                buf.len()
+            }
+        };
+        tag_size + value_size
+    }
+}
+
+/// Looks up `path` (a dot-separated field-name path, e.g. `"a.b.c"`) on
+/// `view`, for debugging tools and config-override systems that want to
+/// address a field by string path without generated-code knowledge of the
+/// message's shape.
+///
+/// Only ever resolves the first segment: this crate's reflection surface
+/// has no sub-message `FieldType` yet (see this module's doc comment on
+/// what [`FieldType`] doesn't cover), so there's no declared field for a
+/// later segment like `"b"` in `"a.b"` to address -- `a` can only ever be
+/// a `String`/`Enum`, never another message to keep walking into. A path
+/// with more than one segment therefore always returns `None`; once
+/// sub-message fields exist, this should grow a loop that walks each
+/// segment through the nested message's own `FieldAccess` impl.
+pub fn get_path<T: FieldAccess>(view: &T, path: &str) -> Option<crate::DynamicValue> {
+    let mut segments = path.split('.');
+    let first = segments.next()?;
+    if segments.next().is_some() {
+        return None;
+    }
+    view.field_by_name(first)
+}
+
+/// A debugging helper, behind the `field-presence-debug` feature, for
+/// dumping which of a message's declared fields are set without a full
+/// reflection stack or per-field `match`. This crate has no has-bits
+/// storage to report on directly -- generated messages store plain values
+/// with implicit proto3 presence -- so "present" here means "not the
+/// field type's default value", the same rule `serialize()` uses to
+/// decide whether to emit a field on the wire.
+#[cfg(feature = "field-presence-debug")]
+pub trait FieldPresence: FieldAccess {
+    /// Returns the `(name, number)` of every declared field currently at
+    /// a non-default value, in descriptor order.
+    fn present_fields(&self) -> alloc::vec::Vec<(&'static str, u32)> {
+        Self::descriptor()
+            .fields
+            .iter()
+            .filter(|field| self.field(field.number).is_some_and(|value| !value.is_default()))
+            .map(|field| (field.name, field.number))
+            .collect()
+    }
+}
+
+#[cfg(feature = "field-presence-debug")]
+impl<T: FieldAccess> FieldPresence for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample_gen::SampleMessage;
+    use crate::DynamicValue;
+
+    #[test]
+    fn get_path_resolves_a_single_segment_by_name() {
+        let message = SampleMessage::new("grace");
+        assert_eq!(get_path(&message, "name"), Some(DynamicValue::String("grace".into())));
+    }
+
+    #[test]
+    fn get_path_rejects_an_unknown_field_name() {
+        let message = SampleMessage::new("grace");
+        assert_eq!(get_path(&message, "missing"), None);
+    }
+
+    #[test]
+    fn get_path_cannot_descend_past_the_first_segment() {
+        let message = SampleMessage::new("grace");
+        assert_eq!(get_path(&message, "name.inner"), None);
+    }
+
+    #[test]
+    fn field_by_json_name_finds_a_field_overriding_its_json_name() {
+        let field = SampleMessage::descriptor().field_by_json_name("colorCode").unwrap();
+        assert_eq!(field.name, "color");
+    }
+
+    #[test]
+    fn field_by_json_name_falls_back_to_the_proto_name_when_not_overridden() {
+        let field = SampleMessage::descriptor().field_by_json_name("name").unwrap();
+        assert_eq!(field.name, "name");
+    }
+
+    #[test]
+    fn field_by_json_name_returns_none_for_an_unknown_name() {
+        assert!(SampleMessage::descriptor().field_by_json_name("missing").is_none());
+    }
+
+    #[test]
+    fn resolved_features_report_implicit_presence_for_both_fields() {
+        let descriptor = SampleMessage::descriptor();
+        for field in descriptor.fields {
+            assert_eq!(field.features.field_presence, FieldPresenceMode::Implicit);
+        }
+    }
+
+    #[test]
+    fn resolved_features_leave_enum_type_unset_for_a_non_enum_field() {
+        let field = SampleMessage::descriptor().field_by_name("name").unwrap();
+        assert_eq!(field.features.enum_type, None);
+    }
+
+    #[test]
+    fn resolved_features_report_the_enum_fields_actual_openness() {
+        let field = SampleMessage::descriptor().field_by_name("color").unwrap();
+        assert_eq!(field.features.enum_type, Some(EnumOpenness::Closed));
+    }
+
+    #[test]
+    fn resolved_features_leave_utf8_validation_unset_for_a_non_string_field() {
+        let field = SampleMessage::descriptor().field_by_name("color").unwrap();
+        assert_eq!(field.features.utf8_validation, None);
+    }
+
+    #[test]
+    fn resolved_features_report_the_string_fields_actual_validation_mode() {
+        let field = SampleMessage::descriptor().field_by_name("name").unwrap();
+        assert_eq!(field.features.utf8_validation, Some(Utf8Validation::Lossy));
+    }
+}
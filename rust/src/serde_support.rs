@@ -0,0 +1,87 @@
+//! `serde::Serialize`/`Deserialize` for generated messages, behind the
+//! `serde` feature.
+//!
+//! Hand-written rather than `#[derive(Serialize)]`, since a proto message's
+//! serde shape (field names, how a `oneof` or unrecognized enum value
+//! serializes) is schema metadata the generator controls, not something a
+//! derive macro can infer from the Rust struct shape alone.
+
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::sample_gen::{Color, SampleMessage};
+use crate::serialized_data::SerializedData;
+use crate::Enum;
+
+impl Serialize for SerializedData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.as_ref())
+    }
+}
+
+impl Serialize for SampleMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("SampleMessage", 2)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("color", self.color.name().unwrap_or("COLOR_UNSPECIFIED"))?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for SampleMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SampleMessageVisitor;
+
+        impl<'de> Visitor<'de> for SampleMessageVisitor {
+            type Value = SampleMessage;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a SampleMessage object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut message = SampleMessage::new("");
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "name" => message.name = map.next_value()?,
+                        "color" => {
+                            let name: String = map.next_value()?;
+                            message.color = Color::from_name(&name)
+                                .ok_or_else(|| de::Error::custom(format!("unknown color {name:?}")))?;
+                        }
+                        _ => {
+                            let _: serde::de::IgnoredAny = map.next_value()?;
+                        }
+                    }
+                }
+                Ok(message)
+            }
+        }
+
+        deserializer.deserialize_map(SampleMessageVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_serde_json() {
+        let mut message = SampleMessage::new("bob");
+        message.color = Color::Red;
+
+        let json = serde_json::to_string(&message).unwrap();
+        let decoded: SampleMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, message);
+    }
+
+    #[test]
+    fn serialized_data_serializes_as_its_raw_bytes() {
+        let data = SerializedData::from(vec![1, 2, 3]);
+        let json = serde_json::to_string(&data).unwrap();
+        assert_eq!(json, "[1,2,3]");
+    }
+}
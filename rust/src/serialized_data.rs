@@ -0,0 +1,146 @@
+//! Bytes produced by serializing a message, kept in a form that can be
+//! shared between owners without a copy per owner.
+//!
+//! Note: this crate's generated messages don't hand back a
+//! `SerializedData` today -- `SampleMessage::serialize` returns a plain
+//! `Vec<u8>` directly, since (per `message.rs`'s doc comment) there's no
+//! arena underneath it to keep alive or release in the first place.
+//! `SerializedData` is a standalone wrapper a caller can put that
+//! `Vec<u8>` into when it wants to hand the same serialized bytes to
+//! several owners without copying per owner.
+
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+/// Serialized bytes, refcounted so multiple owners can share them.
+///
+/// `Clone` only bumps the refcount -- it's `Rc::clone` underneath, same as
+/// [`Self::as_shared`] -- so the copy this type exists to avoid doesn't
+/// happen just from cloning a handle around. It happens "on demand": the
+/// first time a clone actually needs to diverge, e.g. the `Write` impl
+/// below calls `Rc::make_mut`, which copies the buffer only if another
+/// clone is still holding it, then writes into its own private copy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerializedData(Rc<Vec<u8>>);
+
+impl SerializedData {
+    /// Unwraps to an owned `Vec<u8>`. Copies the bytes only if another
+    /// owner (via a live [`Self::as_shared`] handle) is still holding
+    /// this same buffer; otherwise takes ownership directly with no copy.
+    pub fn into_vec(self) -> Vec<u8> {
+        Rc::try_unwrap(self.0).unwrap_or_else(|shared| (*shared).clone())
+    }
+
+    /// A refcounted handle to the same bytes, shared rather than copied.
+    pub fn as_shared(&self) -> Rc<Vec<u8>> {
+        Rc::clone(&self.0)
+    }
+}
+
+impl From<Vec<u8>> for SerializedData {
+    fn from(bytes: Vec<u8>) -> Self {
+        SerializedData(Rc::new(bytes))
+    }
+}
+
+impl AsRef<[u8]> for SerializedData {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Borrow<[u8]> for SerializedData {
+    fn borrow(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl PartialEq<[u8]> for SerializedData {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.0.as_slice() == other
+    }
+}
+
+/// Appends to the shared buffer, copying it first if another owner is
+/// still holding it -- see this type's doc comment. Lets a caller build
+/// up `SerializedData` with `write_all` (e.g. from an `io::copy`) instead
+/// of collecting into a `Vec<u8>` and converting afterwards.
+#[cfg(feature = "std")]
+impl std::io::Write for SerializedData {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Rc::make_mut(&mut self.0).extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn into_vec_returns_the_original_bytes_when_uniquely_owned() {
+        let data = SerializedData::from(alloc::vec![1, 2, 3]);
+        assert_eq!(data.into_vec(), alloc::vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn into_vec_copies_the_bytes_when_a_shared_handle_is_still_alive() {
+        let data = SerializedData::from(alloc::vec![1, 2, 3]);
+        let shared = data.as_shared();
+
+        assert_eq!(data.into_vec(), alloc::vec![1, 2, 3]);
+        assert_eq!(*shared, alloc::vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn as_shared_points_at_the_same_allocation() {
+        let data = SerializedData::from(alloc::vec![1, 2, 3]);
+        let first = data.as_shared();
+        let second = data.as_shared();
+
+        assert!(Rc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn clone_shares_the_allocation_with_the_original() {
+        let data = SerializedData::from(alloc::vec![1, 2, 3]);
+        let cloned = data.clone();
+
+        assert!(Rc::ptr_eq(&data.0, &cloned.0));
+    }
+
+    #[test]
+    fn as_ref_and_borrow_expose_the_underlying_bytes() {
+        let data = SerializedData::from(alloc::vec![1, 2, 3]);
+
+        assert_eq!(AsRef::<[u8]>::as_ref(&data), &[1, 2, 3][..]);
+        assert_eq!(Borrow::<[u8]>::borrow(&data), &[1, 2, 3][..]);
+    }
+
+    #[test]
+    fn partial_eq_compares_against_a_plain_byte_slice() {
+        let data = SerializedData::from(alloc::vec![1, 2, 3]);
+
+        assert_eq!(data, [1u8, 2, 3][..]);
+        assert_ne!(data, [1u8, 2, 4][..]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn write_appends_bytes_copying_only_when_a_shared_handle_is_still_alive() {
+        use std::io::Write;
+
+        let mut data = SerializedData::from(alloc::vec![1, 2, 3]);
+        let shared = data.as_shared();
+
+        data.write_all(&[4, 5]).unwrap();
+
+        assert_eq!(data, [1u8, 2, 3, 4, 5][..]);
+        assert_eq!(*shared, alloc::vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,185 @@
+//! Text format output, i.e. the Rust equivalent of C++'s
+//! `Message::DebugString()`.
+
+use alloc::string::String;
+use core::fmt;
+use core::fmt::Write as _;
+
+use crate::unknown_fields::UnknownFieldSet;
+use crate::wire::{decode_varint, WireType};
+
+/// Implemented by generated messages to print themselves in protobuf's
+/// text format: one `field_name: value` pair per line, nested messages
+/// indented and wrapped in `{ }`.
+///
+/// There's no blanket impl over `Message` because the field names and
+/// value formatting are schema-specific and only the generated code knows
+/// them; this trait just gives every message the same method name and an
+/// `unknown_fields` helper to share.
+pub trait TextFormat {
+    fn write_text_format(&self, out: &mut String);
+
+    fn to_text_format(&self) -> String {
+        let mut out = String::new();
+        self.write_text_format(&mut out);
+        out
+    }
+
+    /// A one-line rendering -- each line of [`TextFormat::to_text_format`]'s
+    /// multi-line output joined with a single space instead of a newline,
+    /// the same squashing C++'s `ShortDebugString` does to its own
+    /// multi-line `DebugString` output. Suitable for a structured log
+    /// line, where embedding a newline would split one log entry into
+    /// several.
+    fn to_compact_text_format(&self) -> String {
+        let multi_line = self.to_text_format();
+        let mut out = String::new();
+        for line in multi_line.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            if !out.is_empty() {
+                out.push(' ');
+            }
+            out.push_str(trimmed);
+        }
+        out
+    }
+
+    /// Borrows `self` behind a [`fmt::Display`] impl that prints
+    /// [`TextFormat::to_compact_text_format`], for `format!("{}", message.as_compact())`
+    /// or logging macros that take any `Display` directly instead of a
+    /// pre-built `String`.
+    fn as_compact(&self) -> Compact<'_, Self> {
+        Compact(self)
+    }
+}
+
+/// See [`TextFormat::as_compact`].
+pub struct Compact<'a, T: ?Sized>(&'a T);
+
+impl<T: TextFormat + ?Sized> fmt::Display for Compact<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.to_compact_text_format())
+    }
+}
+
+/// Renders `view` in text format the same shape as a generated
+/// `write_text_format` impl, but driven generically off its descriptor
+/// instead of hand-written per-field code -- and honoring each field's
+/// `debug_redact` option: a field marked `redact` prints `[REDACTED]`
+/// instead of its value, for logging a message that carries PII without
+/// leaking the PII itself.
+///
+/// Doesn't cover unknown fields (there's no descriptor entry to check
+/// `redact` against for those) or render an `Enum` field's variant name
+/// (this crate's reflection surface has no name table for that -- see
+/// `reflect.rs`'s doc comment on what `FieldType` doesn't cover -- so it
+/// prints the raw number instead, same as `write_unknown_fields` does for
+/// an undeclared varint field).
+pub fn to_redacted_text<T: crate::FieldAccess>(view: &T) -> String {
+    let mut out = String::new();
+    for field in T::descriptor().fields {
+        let Some(value) = view.field(field.number) else { continue };
+        if value.is_default() {
+            continue;
+        }
+        if field.redact {
+            let _ = writeln!(out, "{}: [REDACTED]", field.name);
+            continue;
+        }
+        match value {
+            crate::DynamicValue::String(s) => {
+                let _ = writeln!(out, "{}: {:?}", field.name, s);
+            }
+            crate::DynamicValue::Enum(n) => {
+                let _ = writeln!(out, "{}: {}", field.name, n);
+            }
+        }
+    }
+    out
+}
+
+/// Appends the unknown fields of `set` to `out` in the conventional
+/// `<field_number>: <value>` form text format uses when it doesn't know a
+/// field's declared name.
+pub fn write_unknown_fields(set: &UnknownFieldSet, out: &mut String) {
+    for field in set.iter() {
+        match field.tag.wire_type {
+            WireType::Varint => {
+                let (value, _) = decode_varint(&field.raw_value).unwrap_or((0, &[]));
+                let _ = writeln!(out, "{}: {}", field.tag.field_number, value);
+            }
+            WireType::LengthDelimited => {
+                let _ = writeln!(
+                    out,
+                    "{}: {:?}",
+                    field.tag.field_number,
+                    String::from_utf8_lossy(&field.raw_value)
+                );
+            }
+            WireType::Fixed32 | WireType::Fixed64 => {
+                let _ = writeln!(out, "{}: {:?}", field.tag.field_number, field.raw_value);
+            }
+            WireType::StartGroup => {
+                let _ = writeln!(out, "{} {{", field.tag.field_number);
+                if let Some(nested) = UnknownFieldSet::parse_raw(&field.raw_value) {
+                    write_unknown_fields(&nested, out);
+                }
+                let _ = writeln!(out, "}}");
+            }
+            WireType::EndGroup => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unknown_fields::UnknownField;
+    use crate::wire::Tag;
+
+    #[test]
+    fn renders_unknown_varint_field_by_number() {
+        let mut set = UnknownFieldSet::new();
+        set.push(UnknownField { tag: Tag { field_number: 7, wire_type: WireType::Varint }, raw_value: vec![42] });
+        let mut out = String::new();
+        write_unknown_fields(&set, &mut out);
+        assert_eq!(out, "7: 42\n");
+    }
+
+    #[test]
+    fn to_redacted_text_hides_a_field_marked_debug_redact() {
+        let message = crate::sample_gen::SampleMessage::new("jane.doe@example.com");
+        assert_eq!(to_redacted_text(&message), "name: [REDACTED]\n");
+    }
+
+    #[test]
+    fn to_redacted_text_renders_non_redacted_fields_normally() {
+        use alloc::format;
+        use crate::sample_gen::{Color, SampleMessage};
+
+        let mut message = SampleMessage::new("");
+        message.color = Color::Blue;
+        assert_eq!(to_redacted_text(&message), format!("color: {}\n", i32::from(Color::Blue)));
+    }
+
+    #[test]
+    fn to_compact_text_format_joins_lines_with_spaces() {
+        use crate::sample_gen::{Color, SampleMessage};
+
+        let mut message = SampleMessage::new("bob");
+        message.color = Color::Blue;
+        assert_eq!(message.to_compact_text_format(), "name: \"bob\" color: COLOR_BLUE");
+    }
+
+    #[test]
+    fn as_compact_displays_the_same_string_as_to_compact_text_format() {
+        use alloc::format;
+        use crate::sample_gen::SampleMessage;
+
+        let message = SampleMessage::new("bob");
+        assert_eq!(format!("{}", message.as_compact()), message.to_compact_text_format());
+    }
+}
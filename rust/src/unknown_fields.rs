@@ -0,0 +1,182 @@
+//! Storage for fields a message's `.proto` doesn't declare.
+//!
+//! Unknown fields are the fields present on the wire for a message that the
+//! schema in use doesn't know about (a newer sender, an older schema, or a
+//! field dropped from the `.proto`). The runtime keeps their raw encoded
+//! bytes around unmodified so that parse -> mutate -> serialize round-trips
+//! don't silently drop data, which proxies and other middleboxes rely on.
+
+use alloc::vec::Vec;
+
+use crate::wire::{decode_tag, decode_varint, encode_tag, encode_varint, skip_group, Tag, WireType};
+
+/// One field the schema doesn't declare, as its tag and raw value bytes.
+///
+/// `raw_value` holds exactly the bytes that followed the tag on the wire:
+/// the varint payload for `Varint`, the 4/8 little-endian bytes for
+/// `Fixed32`/`Fixed64`, the length-prefixed payload for
+/// `LengthDelimited`, or (for `StartGroup`) the group's content between
+/// its `StartGroup` tag and matching `EndGroup` tag -- see
+/// `wire::skip_group`. `EndGroup` never appears here on its own; it's
+/// implied by a `StartGroup` entry and re-emitted by `write_to`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct UnknownField {
+    pub tag: Tag,
+    pub raw_value: Vec<u8>,
+}
+
+/// The set of unknown fields carried by a message, in wire order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct UnknownFieldSet {
+    fields: Vec<UnknownField>,
+}
+
+impl UnknownFieldSet {
+    /// A `const fn` (rather than delegating to `Default::default`, which
+    /// trait methods can't be) so generated messages can build a
+    /// `const`-constructible default instance out of this field -- see
+    /// `SampleMessage::default_view`.
+    pub const fn new() -> Self {
+        UnknownFieldSet { fields: Vec::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Iterates the unknown fields in the order they appeared on the wire.
+    /// `DoubleEndedIterator` so callers that care about last-occurrence-wins
+    /// semantics (e.g. [`crate::extensions::MessageExt::get_extension`],
+    /// `SampleMessage::color_raw`) can scan from the back without
+    /// collecting into a `Vec` first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &UnknownField> {
+        self.fields.iter()
+    }
+
+    pub(crate) fn push(&mut self, field: UnknownField) {
+        self.fields.push(field);
+    }
+
+    /// Drops every entry for `field_number`. Used by
+    /// [`crate::extensions::MessageExt`]'s `set_extension`/`clear_extension`,
+    /// since a proto2 extension's value lives here as an ordinary unknown
+    /// field and setting or clearing it means discarding whatever entry is
+    /// currently stored at that field number first; also used when a later
+    /// wire occurrence of a field parses into its declared typed field after
+    /// an earlier occurrence didn't (see `SampleMessage`'s `(2,
+    /// WireType::Varint)` parse arm), so the stale unknown entry doesn't
+    /// outrank the value the typed field now holds.
+    pub(crate) fn remove_field_number(&mut self, field_number: u32) {
+        self.fields.retain(|field| field.tag.field_number != field_number);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.fields.clear();
+    }
+
+    /// Parses `buf` as a schema-less stream of tag/value pairs, treating
+    /// every field as unknown. Used where there's no generated message
+    /// type to parse against -- e.g. rendering a `StartGroup` field's
+    /// content in text format, where the group's own `.proto` shape isn't
+    /// available. Returns `None` on truncated or malformed input.
+    pub(crate) fn parse_raw(mut buf: &[u8]) -> Option<Self> {
+        let mut fields = Self::new();
+        while let Some((tag, rest)) = decode_tag(buf) {
+            buf = rest;
+            match tag.wire_type {
+                WireType::Varint => {
+                    let (value, rest) = decode_varint(buf)?;
+                    let mut raw_value = Vec::new();
+                    encode_varint(value, &mut raw_value);
+                    fields.push(UnknownField { tag, raw_value });
+                    buf = rest;
+                }
+                WireType::Fixed32 => {
+                    let value = buf.get(..4)?;
+                    fields.push(UnknownField { tag, raw_value: value.to_vec() });
+                    buf = &buf[4..];
+                }
+                WireType::Fixed64 => {
+                    let value = buf.get(..8)?;
+                    fields.push(UnknownField { tag, raw_value: value.to_vec() });
+                    buf = &buf[8..];
+                }
+                WireType::LengthDelimited => {
+                    let (len, rest) = decode_varint(buf)?;
+                    let len = len as usize;
+                    let value = rest.get(..len)?;
+                    fields.push(UnknownField { tag, raw_value: value.to_vec() });
+                    buf = &rest[len..];
+                }
+                WireType::StartGroup => {
+                    let (content, rest) = skip_group(buf, tag.field_number)?;
+                    fields.push(UnknownField { tag, raw_value: content.to_vec() });
+                    buf = rest;
+                }
+                WireType::EndGroup => return None,
+            }
+        }
+        // `decode_tag` gives up on a truncated tag or an invalid wire type.
+        if !buf.is_empty() {
+            return None;
+        }
+        Some(fields)
+    }
+
+    /// Re-encodes every unknown field, tag included, appending to `out`.
+    /// Used by generated `serialize()` to preserve unknown data.
+    pub(crate) fn write_to(&self, out: &mut Vec<u8>) {
+        for field in &self.fields {
+            encode_varint(encode_tag(field.tag.field_number, field.tag.wire_type), out);
+            if field.tag.wire_type == WireType::LengthDelimited {
+                encode_varint(field.raw_value.len() as u64, out);
+            }
+            out.extend_from_slice(&field.raw_value);
+            if field.tag.wire_type == WireType::StartGroup {
+                encode_varint(encode_tag(field.tag.field_number, WireType::EndGroup), out);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wire::{decode_tag, Tag};
+
+    #[test]
+    fn write_to_brackets_group_content_with_matching_start_and_end_tags() {
+        let mut content = Vec::new();
+        encode_varint(encode_tag(1, WireType::Varint), &mut content);
+        encode_varint(9, &mut content);
+
+        let mut fields = UnknownFieldSet::new();
+        fields.push(UnknownField {
+            tag: Tag { field_number: 3, wire_type: WireType::StartGroup },
+            raw_value: content,
+        });
+
+        let mut out = Vec::new();
+        fields.write_to(&mut out);
+
+        let (start_tag, rest) = decode_tag(&out).unwrap();
+        assert_eq!(start_tag, Tag { field_number: 3, wire_type: WireType::StartGroup });
+        let (content, rest) = crate::wire::skip_group(rest, 3).unwrap();
+        assert!(rest.is_empty());
+        let (inner_tag, inner_rest) = decode_tag(content).unwrap();
+        assert_eq!(inner_tag, Tag { field_number: 1, wire_type: WireType::Varint });
+        assert_eq!(crate::wire::decode_varint(inner_rest), Some((9, &[][..])));
+    }
+
+    #[test]
+    fn unknown_field_set_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<UnknownFieldSet>();
+    }
+}
@@ -0,0 +1,150 @@
+use core::fmt;
+use core::time::Duration as StdDuration;
+
+/// `google.protobuf.Duration`: a signed, fixed-length span of time, as a
+/// count of seconds and nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Duration {
+    pub seconds: i64,
+    pub nanos: i32,
+}
+
+/// `Duration::seconds`/`nanos` don't agree in sign, `nanos` is outside
+/// `(-1_000_000_000, 1_000_000_000)`, or the value can't be represented by
+/// `std::time::Duration` (which has no sign).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurationError;
+
+impl fmt::Display for DurationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "duration is invalid or cannot be represented as a positive std::time::Duration")
+    }
+}
+
+impl core::error::Error for DurationError {}
+
+impl Duration {
+    /// Adds two durations, carrying any overflow out of `nanos` into
+    /// `seconds` so the result stays in the proto's normal form (`nanos`
+    /// matches `seconds`'s sign and stays within `(-1_000_000_000,
+    /// 1_000_000_000)`). Returns `Err(DurationError)` if the true sum's
+    /// `seconds` wouldn't fit in an `i64`, rather than silently wrapping.
+    pub fn checked_add(self, other: Duration) -> Result<Duration, DurationError> {
+        let nanos_sum = self.nanos as i64 + other.nanos as i64;
+        // Plain `/`/`%` truncate toward zero, which is exactly what keeps
+        // the carried `nanos` remainder's sign matching `nanos_sum`'s --
+        // unlike `Timestamp::checked_add`, whose `nanos` must stay
+        // non-negative regardless of sign and so needs `div_euclid`/
+        // `rem_euclid` instead.
+        let carry_seconds = nanos_sum / 1_000_000_000;
+        let nanos = (nanos_sum % 1_000_000_000) as i32;
+        let seconds = self
+            .seconds
+            .checked_add(other.seconds)
+            .and_then(|seconds| seconds.checked_add(carry_seconds))
+            .ok_or(DurationError)?;
+        // The two sums can still disagree in sign (5.2s - 2.9s gives 3s and
+        // -0.7s); move one second across to restore the normal form.
+        let (seconds, nanos) = if seconds > 0 && nanos < 0 {
+            (seconds - 1, nanos + 1_000_000_000)
+        } else if seconds < 0 && nanos > 0 {
+            (seconds + 1, nanos - 1_000_000_000)
+        } else {
+            (seconds, nanos)
+        };
+        Ok(Duration { seconds, nanos })
+    }
+
+    /// Subtracts `other` from `self` -- see [`Duration::checked_add`].
+    /// Returns `Err(DurationError)` on the same overflow, plus the edge
+    /// case where `other` can't be negated (`i64::MIN` seconds or
+    /// `i32::MIN` nanos).
+    pub fn checked_sub(self, other: Duration) -> Result<Duration, DurationError> {
+        let negated = Duration {
+            seconds: other.seconds.checked_neg().ok_or(DurationError)?,
+            nanos: other.nanos.checked_neg().ok_or(DurationError)?,
+        };
+        self.checked_add(negated)
+    }
+}
+
+impl From<StdDuration> for Duration {
+    fn from(value: StdDuration) -> Self {
+        Duration { seconds: value.as_secs() as i64, nanos: value.subsec_nanos() as i32 }
+    }
+}
+
+impl TryFrom<Duration> for StdDuration {
+    type Error = DurationError;
+
+    fn try_from(value: Duration) -> Result<Self, Self::Error> {
+        if value.nanos <= -1_000_000_000 || value.nanos >= 1_000_000_000 {
+            return Err(DurationError);
+        }
+        if value.seconds < 0 || value.nanos < 0 {
+            // std::time::Duration cannot represent negative spans.
+            return Err(DurationError);
+        }
+        Ok(StdDuration::new(value.seconds as u64, value.nanos as u32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_a_positive_duration() {
+        let std_duration = StdDuration::new(12, 345);
+        let duration = Duration::from(std_duration);
+        assert_eq!(duration, Duration { seconds: 12, nanos: 345 });
+        assert_eq!(StdDuration::try_from(duration), Ok(std_duration));
+    }
+
+    #[test]
+    fn rejects_negative_durations() {
+        let duration = Duration { seconds: -1, nanos: 0 };
+        assert_eq!(StdDuration::try_from(duration), Err(DurationError));
+    }
+
+    #[test]
+    fn rejects_out_of_range_nanos() {
+        let duration = Duration { seconds: 0, nanos: 1_000_000_000 };
+        assert_eq!(StdDuration::try_from(duration), Err(DurationError));
+    }
+
+    #[test]
+    fn ordering_compares_longer_durations_as_greater() {
+        assert!(Duration { seconds: 1, nanos: 0 } < Duration { seconds: 1, nanos: 500_000_000 });
+        assert!(Duration { seconds: -2, nanos: -100_000_000 } < Duration { seconds: -1, nanos: -900_000_000 });
+    }
+
+    #[test]
+    fn checked_add_carries_a_nanos_overflow_into_seconds() {
+        let a = Duration { seconds: 1, nanos: 600_000_000 };
+        let b = Duration { seconds: 1, nanos: 700_000_000 };
+        assert_eq!(a.checked_add(b), Ok(Duration { seconds: 3, nanos: 300_000_000 }));
+    }
+
+    #[test]
+    fn checked_add_carries_a_negative_nanos_overflow_into_seconds() {
+        let a = Duration { seconds: -1, nanos: -600_000_000 };
+        let b = Duration { seconds: 0, nanos: -700_000_000 };
+        assert_eq!(a.checked_add(b), Ok(Duration { seconds: -2, nanos: -300_000_000 }));
+    }
+
+    #[test]
+    fn checked_add_reports_an_overflowing_seconds_sum() {
+        let a = Duration { seconds: i64::MAX, nanos: 0 };
+        let b = Duration { seconds: 1, nanos: 0 };
+        assert_eq!(a.checked_add(b), Err(DurationError));
+    }
+
+    #[test]
+    fn checked_sub_is_the_inverse_of_checked_add() {
+        let a = Duration { seconds: 5, nanos: 200_000_000 };
+        let b = Duration { seconds: 2, nanos: 900_000_000 };
+        let difference = a.checked_sub(b).unwrap();
+        assert_eq!(difference.checked_add(b), Ok(a));
+    }
+}
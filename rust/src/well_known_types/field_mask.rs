@@ -0,0 +1,128 @@
+//! `google.protobuf.FieldMask`: a set of dotted field paths used to say
+//! which parts of a message a request reads or writes.
+
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+
+/// A `google.protobuf.FieldMask`, stored as a de-duplicated, sorted set of
+/// paths so `merge`/`intersect` are simple set operations.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FieldMask {
+    paths: BTreeSet<String>,
+}
+
+impl FieldMask {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_paths<I, S>(paths: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        FieldMask { paths: paths.into_iter().map(Into::into).collect() }
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.paths.iter().map(String::as_str)
+    }
+
+    pub fn contains_path(&self, path: &str) -> bool {
+        // A mask containing an ancestor path ("a") covers every field
+        // nested under it ("a.b.c"), matching FieldMask's documented
+        // semantics for merging/applying masks to messages.
+        self.paths.contains(path)
+            || path
+                .match_indices('.')
+                .any(|(i, _)| self.paths.contains(&path[..i]))
+    }
+
+    /// The union of `self` and `other`'s paths.
+    pub fn merge(&self, other: &FieldMask) -> FieldMask {
+        FieldMask { paths: self.paths.union(&other.paths).cloned().collect() }
+    }
+
+    /// The paths present in both `self` and `other`.
+    pub fn intersect(&self, other: &FieldMask) -> FieldMask {
+        FieldMask { paths: self.paths.intersection(&other.paths).cloned().collect() }
+    }
+
+    /// Keeps only the fields of `message` named by this mask, clearing
+    /// every other top-level field it's given.
+    pub fn apply<F: FieldMaskTarget>(&self, message: &mut F) {
+        for field in F::FIELD_NAMES {
+            if !self.contains_path(field) {
+                message.clear_field(field);
+            }
+        }
+    }
+}
+
+/// Implemented by generated messages so `FieldMask::apply` can clear
+/// fields it doesn't cover without a per-message match statement.
+pub trait FieldMaskTarget {
+    const FIELD_NAMES: &'static [&'static str];
+
+    fn clear_field(&mut self, name: &str);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_is_the_union_of_paths() {
+        let a = FieldMask::from_paths(["a", "b"]);
+        let b = FieldMask::from_paths(["b", "c"]);
+        assert_eq!(a.merge(&b), FieldMask::from_paths(["a", "b", "c"]));
+    }
+
+    #[test]
+    fn intersect_is_the_common_paths() {
+        let a = FieldMask::from_paths(["a", "b"]);
+        let b = FieldMask::from_paths(["b", "c"]);
+        assert_eq!(a.intersect(&b), FieldMask::from_paths(["b"]));
+    }
+
+    #[test]
+    fn contains_path_covers_nested_fields_under_an_ancestor() {
+        let mask = FieldMask::from_paths(["a"]);
+        assert!(mask.contains_path("a.b.c"));
+        assert!(!mask.contains_path("other"));
+    }
+
+    struct Example {
+        a: i32,
+        b: i32,
+        a_cleared: bool,
+        b_cleared: bool,
+    }
+
+    impl FieldMaskTarget for Example {
+        const FIELD_NAMES: &'static [&'static str] = &["a", "b"];
+
+        fn clear_field(&mut self, name: &str) {
+            match name {
+                "a" => {
+                    self.a = 0;
+                    self.a_cleared = true;
+                }
+                "b" => {
+                    self.b = 0;
+                    self.b_cleared = true;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn apply_clears_fields_not_named_by_the_mask() {
+        let mut example = Example { a: 1, b: 2, a_cleared: false, b_cleared: false };
+        FieldMask::from_paths(["a"]).apply(&mut example);
+        assert!(!example.a_cleared);
+        assert!(example.b_cleared);
+        assert_eq!(example.b, 0);
+    }
+}
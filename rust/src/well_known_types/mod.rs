@@ -0,0 +1,21 @@
+//! Hand-maintained Rust bindings for the well-known types in
+//! `google/protobuf/*.proto`. Generated code for messages that embed one of
+//! these types re-exports the type from here rather than generating its own
+//! copy, mirroring how every other language's runtime special-cases them.
+
+mod duration;
+mod empty;
+mod field_mask;
+mod structpb;
+mod timestamp;
+mod wrappers;
+
+pub use duration::{Duration, DurationError};
+pub use empty::Empty;
+pub use field_mask::{FieldMask, FieldMaskTarget};
+pub use structpb::{Struct, Value};
+pub use timestamp::{Timestamp, TimestampError};
+pub use wrappers::{
+    BoolValue, BytesValue, DoubleValue, FloatValue, Int32Value, Int64Value, StringValue,
+    UInt32Value, UInt64Value,
+};
@@ -0,0 +1,614 @@
+//! `google.protobuf.Struct` / `google.protobuf.Value` / `google.protobuf.ListValue`.
+//!
+//! These model arbitrary untyped JSON-like data. Building one by hand
+//! through the proto's `oneof` + `map` fields is painful, so this module
+//! adds the ergonomics every other language's runtime gives these types:
+//! `From` conversions for the primitive variants and `Index` so nested
+//! values can be read with `value["key"]["nested"]`.
+//!
+//! `Value`/`Struct` nest arbitrarily deeply (a `Struct` field can hold a
+//! `Value`, which can hold another `Struct`, ...), so the usual derived
+//! `Clone`/`PartialEq` -- which recurse one stack frame per level of
+//! nesting -- can overflow the stack on a pathologically deep tree (a
+//! malicious or buggy sender nesting hundreds of thousands of levels
+//! deep). `Value`'s `Clone` and `PartialEq` below walk the tree with an
+//! explicit heap-allocated stack instead of Rust call-stack recursion, so
+//! depth is bounded only by available memory. `Debug` stays recursive --
+//! call-stack depth proportional to nesting is fine for a bounded-depth
+//! diagnostic print -- but caps how deep it'll follow nesting so printing
+//! a deep tree can't itself overflow the stack.
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Index;
+
+// A `static`, not a `const`: taking `&NULL` needs a value that lives for
+// `'static` without being dropped at the end of some temporary's scope,
+// and `Value`'s custom `Drop` impl (see below) blocks the usual
+// const-to-promoted-static optimization that would otherwise give a bare
+// `const` the same property.
+static NULL: Value = Value::Null;
+
+/// How many levels of nesting [`Value`]'s `Debug` impl will follow before
+/// printing `...` instead of recursing further.
+const MAX_DEBUG_DEPTH: usize = 32;
+
+/// `google.protobuf.Value`: one arbitrary, dynamically-typed JSON value.
+#[derive(Default)]
+pub enum Value {
+    #[default]
+    Null,
+    Number(f64),
+    String(String),
+    Bool(bool),
+    Struct(Struct),
+    List(Vec<Value>),
+}
+
+/// `google.protobuf.Struct`: a map of `string` to `Value`, kept in key
+/// order (a `BTreeMap` so iteration is deterministic, which matters for
+/// JSON/text output).
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct Struct(BTreeMap<String, Value>);
+
+impl Struct {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.0.get(key)
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Value>) -> Option<Value> {
+        self.0.insert(key.into(), value.into())
+    }
+
+    /// Iterates borrowed `(key, value)` pairs in key order. The returned
+    /// iterator's `len()` (via `ExactSizeIterator`) always matches
+    /// `self.len()`, since it's a thin wrapper over `BTreeMap`'s own
+    /// size-tracking iterator.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&str, &Value)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+/// Consumes the `Struct`, yielding owned `(String, Value)` pairs in key
+/// order -- for moving its entries into another data structure without
+/// cloning them first.
+impl IntoIterator for Struct {
+    type Item = (String, Value);
+    type IntoIter = alloc::collections::btree_map::IntoIter<String, Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// One step of the explicit work stack [`Value`]'s `Clone` impl walks
+/// instead of recursing per level of nesting. Holds the in-progress
+/// container (its not-yet-consumed source iterator, plus what's been built
+/// so far) and, for a struct entry, the key the pending value belongs
+/// under.
+enum CloneFrame<'a> {
+    List(core::slice::Iter<'a, Value>, Vec<Value>),
+    Struct(alloc::collections::btree_map::Iter<'a, String, Value>, BTreeMap<String, Value>, &'a String),
+}
+
+impl Clone for Value {
+    fn clone(&self) -> Self {
+        let mut stack: Vec<CloneFrame> = Vec::new();
+        let mut current = self;
+        let mut result;
+
+        loop {
+            // Descend, pushing a frame for every container on the way down
+            // to the next leaf (or empty container) that needs no further
+            // descent.
+            result = loop {
+                match current {
+                    Value::Null => break Value::Null,
+                    Value::Number(n) => break Value::Number(*n),
+                    Value::String(s) => break Value::String(s.clone()),
+                    Value::Bool(b) => break Value::Bool(*b),
+                    Value::List(items) => {
+                        let mut iter = items.iter();
+                        match iter.next() {
+                            Some(first) => {
+                                stack.push(CloneFrame::List(iter, Vec::with_capacity(items.len())));
+                                current = first;
+                            }
+                            None => break Value::List(Vec::new()),
+                        }
+                    }
+                    Value::Struct(Struct(map)) => {
+                        let mut iter = map.iter();
+                        match iter.next() {
+                            Some((key, first)) => {
+                                stack.push(CloneFrame::Struct(iter, BTreeMap::new(), key));
+                                current = first;
+                            }
+                            None => break Value::Struct(Struct(BTreeMap::new())),
+                        }
+                    }
+                }
+            };
+
+            // Fold `result` into its parent frame, advancing that frame's
+            // iterator. If the parent has more children, descend into the
+            // next one; otherwise the parent is done, so its own result
+            // folds into *its* parent, and so on up the stack.
+            loop {
+                match stack.pop() {
+                    None => return result,
+                    Some(CloneFrame::List(mut iter, mut built)) => {
+                        built.push(result);
+                        match iter.next() {
+                            Some(next) => {
+                                stack.push(CloneFrame::List(iter, built));
+                                current = next;
+                                break;
+                            }
+                            None => result = Value::List(built),
+                        }
+                    }
+                    Some(CloneFrame::Struct(mut iter, mut built, key)) => {
+                        built.insert(key.clone(), result);
+                        match iter.next() {
+                            Some((next_key, next_value)) => {
+                                stack.push(CloneFrame::Struct(iter, built, next_key));
+                                current = next_value;
+                                break;
+                            }
+                            None => result = Value::Struct(Struct(built)),
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// One step of the explicit work stack [`Value`]'s `PartialEq` impl walks
+/// instead of recursing per level of nesting.
+enum EqFrame<'a> {
+    List(core::slice::Iter<'a, Value>, core::slice::Iter<'a, Value>),
+    Struct(
+        alloc::collections::btree_map::Iter<'a, String, Value>,
+        alloc::collections::btree_map::Iter<'a, String, Value>,
+    ),
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        let mut stack: Vec<EqFrame> = Vec::new();
+        let mut a = self;
+        let mut b = other;
+
+        loop {
+            let equal = loop {
+                match (a, b) {
+                    (Value::Null, Value::Null) => break true,
+                    (Value::Number(x), Value::Number(y)) => break x == y,
+                    (Value::String(x), Value::String(y)) => break x == y,
+                    (Value::Bool(x), Value::Bool(y)) => break x == y,
+                    (Value::List(xs), Value::List(ys)) => {
+                        if xs.len() != ys.len() {
+                            break false;
+                        }
+                        let mut xi = xs.iter();
+                        let mut yi = ys.iter();
+                        match (xi.next(), yi.next()) {
+                            (Some(x0), Some(y0)) => {
+                                stack.push(EqFrame::List(xi, yi));
+                                a = x0;
+                                b = y0;
+                            }
+                            _ => break true, // both empty, since lengths matched
+                        }
+                    }
+                    (Value::Struct(Struct(xm)), Value::Struct(Struct(ym))) => {
+                        if xm.len() != ym.len() {
+                            break false;
+                        }
+                        let mut xi = xm.iter();
+                        let mut yi = ym.iter();
+                        match (xi.next(), yi.next()) {
+                            (Some((xk, xv)), Some((yk, yv))) => {
+                                if xk != yk {
+                                    break false;
+                                }
+                                stack.push(EqFrame::Struct(xi, yi));
+                                a = xv;
+                                b = yv;
+                            }
+                            _ => break true, // both empty, since lengths matched
+                        }
+                    }
+                    _ => break false, // different variants
+                }
+            };
+
+            if !equal {
+                return false;
+            }
+
+            loop {
+                match stack.pop() {
+                    None => return true,
+                    Some(EqFrame::List(mut xi, mut yi)) => match (xi.next(), yi.next()) {
+                        (Some(x), Some(y)) => {
+                            stack.push(EqFrame::List(xi, yi));
+                            a = x;
+                            b = y;
+                            break;
+                        }
+                        _ => continue,
+                    },
+                    Some(EqFrame::Struct(mut xi, mut yi)) => match (xi.next(), yi.next()) {
+                        (Some((xk, xv)), Some((yk, yv))) => {
+                            if xk != yk {
+                                return false;
+                            }
+                            stack.push(EqFrame::Struct(xi, yi));
+                            a = xv;
+                            b = yv;
+                            break;
+                        }
+                        _ => continue,
+                    },
+                }
+            }
+        }
+    }
+}
+impl Eq for Value {}
+
+impl Drop for Value {
+    fn drop(&mut self) {
+        // The compiler-generated drop glue for `Vec<Value>`/`BTreeMap<_,
+        // Value>` recurses one stack frame per level of nesting, same as
+        // the naive `Clone`/`PartialEq` this module avoids above -- so
+        // dropping a pathologically deep tree through it can overflow the
+        // stack even though nothing else touched it. Detach this value's
+        // direct children into a work list first, so `self`'s fields are
+        // already empty by the time they actually drop, then drain that
+        // list in a flat loop: each popped value's own children are
+        // likewise detached before it drops, so no single drop ever sees
+        // more than one level of nesting.
+        let mut pending: Vec<Value> = match self {
+            Value::List(items) => core::mem::take(items),
+            Value::Struct(Struct(map)) => core::mem::take(map).into_values().collect(),
+            _ => return,
+        };
+
+        while let Some(mut value) = pending.pop() {
+            match &mut value {
+                Value::List(items) => pending.extend(core::mem::take(items)),
+                Value::Struct(Struct(map)) => pending.extend(core::mem::take(map).into_values()),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_depth_limited(f, 0)
+    }
+}
+
+impl Value {
+    /// `google.protobuf.NullValue`'s one value, spelled as a constructor
+    /// rather than the bare `Value::Null` variant -- for call sites
+    /// building a `Value` alongside `Value::from(...)` conversions that
+    /// would otherwise be the only way to get one.
+    pub fn null() -> Self {
+        Value::Null
+    }
+
+    fn fmt_depth_limited(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        if depth >= MAX_DEBUG_DEPTH {
+            return write!(f, "...");
+        }
+        match self {
+            Value::Null => write!(f, "Null"),
+            Value::Number(n) => write!(f, "Number({n:?})"),
+            Value::String(s) => write!(f, "String({s:?})"),
+            Value::Bool(b) => write!(f, "Bool({b:?})"),
+            Value::Struct(s) => {
+                write!(f, "Struct(")?;
+                s.fmt_depth_limited(f, depth + 1)?;
+                write!(f, ")")
+            }
+            Value::List(items) => {
+                write!(f, "List([")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    item.fmt_depth_limited(f, depth + 1)?;
+                }
+                write!(f, "])")
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Struct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_depth_limited(f, 0)
+    }
+}
+
+impl Struct {
+    fn fmt_depth_limited(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        if depth >= MAX_DEBUG_DEPTH {
+            return write!(f, "...");
+        }
+        write!(f, "{{")?;
+        for (i, (key, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{key:?}: ")?;
+            value.fmt_depth_limited(f, depth + 1)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<Struct> for Value {
+    fn from(value: Struct) -> Self {
+        Value::Struct(value)
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(value: Vec<Value>) -> Self {
+        Value::List(value)
+    }
+}
+
+/// `google.protobuf.ListValue` is `Value::List(Vec<Value>)` here rather than
+/// a separate type (see this module's doc comment), so the `ListValue:
+/// FromIterator<Value>` ergonomics every other language's runtime gives that
+/// type land on `Value` itself: `values.into_iter().collect()` builds a
+/// `Value::List` directly, without a `Vec<Value>` collected first just to
+/// feed [`Value::from`].
+impl FromIterator<Value> for Value {
+    fn from_iter<I: IntoIterator<Item = Value>>(iter: I) -> Self {
+        Value::List(Vec::from_iter(iter))
+    }
+}
+
+impl Index<&str> for Value {
+    type Output = Value;
+
+    /// Returns the nested value at `key`, or `Value::Null` if this value
+    /// is not a `Struct` or has no such key — matching JavaScript/`serde_json`
+    /// indexing ergonomics rather than panicking, since missing keys are the
+    /// common case when navigating loosely-typed data.
+    fn index(&self, key: &str) -> &Value {
+        match self {
+            Value::Struct(fields) => fields.get(key).unwrap_or(&NULL),
+            _ => &NULL,
+        }
+    }
+}
+
+impl Index<&str> for Struct {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<Value> for serde_json::Value {
+    fn from(mut value: Value) -> serde_json::Value {
+        // `Value` has a custom `Drop` (see above), which rules out moving
+        // a field out of a by-value match arm -- the compiler can no
+        // longer prove the rest of `value` is still whole enough to drop.
+        // `mem::take` each field out explicitly instead, leaving a cheap
+        // default behind for `value` to drop trivially once this match
+        // returns.
+        match &mut value {
+            Value::Null => serde_json::Value::Null,
+            Value::Number(n) => serde_json::Number::from_f64(*n)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null),
+            Value::String(s) => serde_json::Value::String(core::mem::take(s)),
+            Value::Bool(b) => serde_json::Value::Bool(*b),
+            Value::Struct(s) => serde_json::Value::from(core::mem::take(s)),
+            Value::List(items) => {
+                serde_json::Value::Array(core::mem::take(items).into_iter().map(Into::into).collect())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<Struct> for serde_json::Value {
+    fn from(value: Struct) -> serde_json::Value {
+        serde_json::Value::Object(value.0.into_iter().map(|(k, v)| (k, v.into())).collect())
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Value> for Value {
+    fn from(value: serde_json::Value) -> Value {
+        match value {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Number(n) => Value::Number(n.as_f64().unwrap_or(0.0)),
+            serde_json::Value::String(s) => Value::String(s),
+            serde_json::Value::Bool(b) => Value::Bool(b),
+            serde_json::Value::Array(items) => Value::List(items.into_iter().map(Into::into).collect()),
+            serde_json::Value::Object(fields) => {
+                Value::Struct(Struct(fields.into_iter().map(|(k, v)| (k, v.into())).collect()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_indexing_reads_through_structs() {
+        let mut inner = Struct::new();
+        inner.insert("nested", "hi");
+        let mut outer = Struct::new();
+        outer.insert("key", inner);
+
+        let value = Value::from(outer);
+        assert_eq!(value["key"]["nested"], Value::String("hi".to_string()));
+    }
+
+    #[test]
+    fn missing_keys_yield_null_instead_of_panicking() {
+        let value = Value::from(Struct::new());
+        assert_eq!(value["missing"]["also_missing"], Value::Null);
+    }
+
+    #[test]
+    fn primitive_conversions() {
+        assert_eq!(Value::from(1.5), Value::Number(1.5));
+        assert_eq!(Value::from("x"), Value::String("x".to_string()));
+        assert_eq!(Value::from(true), Value::Bool(true));
+    }
+
+    #[test]
+    fn null_matches_the_null_variant() {
+        assert_eq!(Value::null(), Value::Null);
+    }
+
+    #[test]
+    fn collecting_values_builds_a_list() {
+        let list: Value = [Value::from(1.0), Value::from(true), Value::null()].into_iter().collect();
+        assert_eq!(list, Value::List(vec![Value::from(1.0), Value::from(true), Value::null()]));
+    }
+
+    #[test]
+    fn iter_len_matches_struct_len() {
+        let mut s = Struct::new();
+        s.insert("a", 1.0);
+        s.insert("b", 2.0);
+
+        assert_eq!(s.iter().len(), s.len());
+        assert_eq!(s.iter().len(), 2);
+    }
+
+    #[test]
+    fn into_iter_yields_owned_pairs_in_key_order() {
+        let mut s = Struct::new();
+        s.insert("b", 2.0);
+        s.insert("a", 1.0);
+
+        let pairs: Vec<(String, Value)> = s.into_iter().collect();
+        assert_eq!(pairs, vec![("a".to_string(), Value::Number(1.0)), ("b".to_string(), Value::Number(2.0))]);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn round_trips_through_serde_json() {
+        let mut s = Struct::new();
+        s.insert("a", 1.0);
+        s.insert("b", vec![Value::from(true), Value::Null]);
+        let value = Value::from(s);
+
+        let json: serde_json::Value = value.clone().into();
+        let back: Value = json.into();
+        assert_eq!(back, value);
+    }
+
+    /// A `List` chain nested this deep would overflow the stack under
+    /// naive per-level recursion; `Clone`/`PartialEq` must walk it with an
+    /// explicit heap stack instead.
+    fn deeply_nested_list(depth: usize) -> Value {
+        let mut value = Value::Null;
+        for _ in 0..depth {
+            value = Value::List(vec![value]);
+        }
+        value
+    }
+
+    #[test]
+    fn clone_does_not_overflow_the_stack_on_deep_nesting() {
+        let value = deeply_nested_list(100_000);
+        let cloned = value.clone();
+        assert_eq!(cloned, value);
+    }
+
+    #[test]
+    fn eq_does_not_overflow_the_stack_on_deep_nesting() {
+        let a = deeply_nested_list(100_000);
+        let b = deeply_nested_list(100_000);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn eq_detects_a_mismatch_at_the_bottom_of_deep_nesting() {
+        let a = deeply_nested_list(100_000);
+
+        let mut b = Value::Bool(true);
+        for _ in 0..100_000 {
+            b = Value::List(vec![b]);
+        }
+
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn eq_short_circuits_on_mismatched_length_without_descending() {
+        let short = Value::List(vec![Value::Null]);
+        let long = deeply_nested_list(100_000);
+        assert_ne!(short, long);
+    }
+
+    #[test]
+    fn debug_truncates_past_the_max_depth_instead_of_overflowing_the_stack() {
+        let value = deeply_nested_list(100_000);
+        // Must not overflow the stack, and must actually stop recursing
+        // rather than formatting all 100k levels.
+        let rendered = alloc::format!("{value:?}");
+        assert!(rendered.len() < 10_000);
+        assert!(rendered.contains("..."));
+    }
+}
@@ -0,0 +1,149 @@
+//! `google/protobuf/wrappers.proto`: boxed primitives, used so a proto3
+//! field that lacks native presence tracking (e.g. `int32`) can still be
+//! optional on the wire.
+//!
+//! Each wrapper converts to and from its primitive with `From`/`Into`, so a
+//! field typed `Optional<WrapperType>` in generated code can be set with a
+//! bare primitive (`msg.set_count(5)`) rather than constructing the wrapper
+//! message by hand.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+macro_rules! wrapper {
+    ($name:ident, $primitive:ty, $doc:literal, #[derive($($extra:ident),*)]) => {
+        #[doc = $doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Default, $($extra),*)]
+        pub struct $name {
+            pub value: $primitive,
+        }
+
+        impl From<$primitive> for $name {
+            fn from(value: $primitive) -> Self {
+                $name { value }
+            }
+        }
+
+        impl From<$name> for $primitive {
+            fn from(wrapper: $name) -> Self {
+                wrapper.value
+            }
+        }
+    };
+}
+
+wrapper!(Int32Value, i32, "`google.protobuf.Int32Value`", #[derive(Eq, PartialOrd, Ord, Hash)]);
+wrapper!(Int64Value, i64, "`google.protobuf.Int64Value`", #[derive(Eq, PartialOrd, Ord, Hash)]);
+wrapper!(UInt32Value, u32, "`google.protobuf.UInt32Value`", #[derive(Eq, PartialOrd, Ord, Hash)]);
+wrapper!(UInt64Value, u64, "`google.protobuf.UInt64Value`", #[derive(Eq, PartialOrd, Ord, Hash)]);
+// f32/f64 don't implement Eq/Ord/Hash (NaN), so these only get PartialEq/PartialOrd.
+wrapper!(FloatValue, f32, "`google.protobuf.FloatValue`", #[derive(PartialOrd)]);
+wrapper!(DoubleValue, f64, "`google.protobuf.DoubleValue`", #[derive(PartialOrd)]);
+wrapper!(BoolValue, bool, "`google.protobuf.BoolValue`", #[derive(Eq, PartialOrd, Ord, Hash)]);
+
+/// `google.protobuf.StringValue`. Defined by hand rather than through the
+/// `wrapper!` macro since `String` isn't `Copy`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct StringValue {
+    pub value: String,
+}
+
+impl From<String> for StringValue {
+    fn from(value: String) -> Self {
+        StringValue { value }
+    }
+}
+
+impl From<&str> for StringValue {
+    fn from(value: &str) -> Self {
+        StringValue { value: value.to_string() }
+    }
+}
+
+impl From<StringValue> for String {
+    fn from(wrapper: StringValue) -> Self {
+        wrapper.value
+    }
+}
+
+/// `google.protobuf.BytesValue`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct BytesValue {
+    pub value: Vec<u8>,
+}
+
+impl From<Vec<u8>> for BytesValue {
+    fn from(value: Vec<u8>) -> Self {
+        BytesValue { value }
+    }
+}
+
+impl From<BytesValue> for Vec<u8> {
+    fn from(wrapper: BytesValue) -> Self {
+        wrapper.value
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl BytesValue {
+    /// Converts this value into a `bytes::Bytes` without copying --
+    /// `Bytes::from(Vec<u8>)` takes ownership of the `Vec`'s existing
+    /// allocation directly. Useful for handing a payload to a
+    /// hyper/tonic-style stack that expects `Bytes` rather than `Vec<u8>`.
+    pub fn into_bytes_shared(self) -> bytes::Bytes {
+        bytes::Bytes::from(self.value)
+    }
+
+    /// Sets this value from a `bytes::Bytes`. Unlike `into_bytes_shared`,
+    /// this always copies: this crate's bytes fields are plain owned
+    /// `Vec<u8>` (as `BytesValue::value` is), not a buffer a `Bytes`
+    /// could alias without copying, so there's no kernel-level sharing to
+    /// hand off into the other direction.
+    pub fn set_bytes_shared(&mut self, value: bytes::Bytes) {
+        self.value = value.to_vec();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primitive_wrappers_round_trip() {
+        let wrapped: Int32Value = 5.into();
+        assert_eq!(wrapped, Int32Value { value: 5 });
+        let back: i32 = wrapped.into();
+        assert_eq!(back, 5);
+    }
+
+    #[test]
+    fn option_of_primitive_converts_via_map_into() {
+        let field: Option<i32> = Some(7);
+        let wrapped: Option<Int32Value> = field.map(Into::into);
+        assert_eq!(wrapped, Some(Int32Value { value: 7 }));
+    }
+
+    #[test]
+    fn string_and_bytes_wrappers_round_trip() {
+        let wrapped: StringValue = "hi".into();
+        assert_eq!(String::from(wrapped), "hi");
+
+        let wrapped: BytesValue = vec![1, 2, 3].into();
+        assert_eq!(Vec::from(wrapped), vec![1, 2, 3]);
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn into_bytes_shared_preserves_the_payload() {
+        let wrapped: BytesValue = vec![1, 2, 3].into();
+        assert_eq!(wrapped.into_bytes_shared(), bytes::Bytes::from(vec![1, 2, 3]));
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn set_bytes_shared_overwrites_the_value() {
+        let mut wrapped = BytesValue::default();
+        wrapped.set_bytes_shared(bytes::Bytes::from(vec![4, 5, 6]));
+        assert_eq!(wrapped.value, vec![4, 5, 6]);
+    }
+}
@@ -0,0 +1,293 @@
+//! Minimal protobuf wire-format primitives shared by generated code and the
+//! runtime's unknown-field storage.
+//!
+//! Note: there's no per-element FFI crossing to batch here, because this
+//! crate has no C/upb kernel underneath `RepeatedIter` or `upb_Array_Get`
+//! to call through in the first place -- generated messages hold their
+//! repeated fields as plain `Vec<T>`, so reading or writing a range is
+//! already a direct, O(1)-crossing slice operation (`&vec[start..]`) with
+//! no FFI boundary to cut down.
+//!
+//! There's likewise no upb table-driven/SIMD decoder to enable here, since
+//! this crate has no upb kernel build to opt into one from. What's
+//! available in pure Rust without target-specific intrinsics is a
+//! single-byte fast path for `decode_varint` -- the common case for field
+//! tags and small int32/bool values -- behind the `fast-varint` feature
+//! (on by default; disable it for targets where the extra branch doesn't
+//! pay for itself).
+
+use alloc::vec::Vec;
+
+/// The wire types defined by the protobuf encoding.
+///
+/// `StartGroup`/`EndGroup` back the deprecated proto2 `group` field syntax
+/// and editions' `DELIMITED` message encoding: unlike `LengthDelimited`,
+/// a group has no length prefix, so its content is whatever tag/value
+/// pairs come between the `StartGroup` tag and a matching `EndGroup` tag
+/// with the same field number (see [`skip_group`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum WireType {
+    Varint = 0,
+    Fixed64 = 1,
+    LengthDelimited = 2,
+    StartGroup = 3,
+    EndGroup = 4,
+    Fixed32 = 5,
+}
+
+impl WireType {
+    pub fn from_tag_byte(value: u64) -> Option<Self> {
+        match value & 0x7 {
+            0 => Some(Self::Varint),
+            1 => Some(Self::Fixed64),
+            2 => Some(Self::LengthDelimited),
+            3 => Some(Self::StartGroup),
+            4 => Some(Self::EndGroup),
+            5 => Some(Self::Fixed32),
+            _ => None,
+        }
+    }
+}
+
+/// A parsed `(field_number, wire_type)` tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Tag {
+    pub field_number: u32,
+    pub wire_type: WireType,
+}
+
+pub fn encode_varint(mut value: u64, out: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+pub fn decode_varint(buf: &[u8]) -> Option<(u64, &[u8])> {
+    // Fast path for the overwhelmingly common case: a varint that fits in
+    // a single byte (every field number below 16, and every small int32
+    // field value). Skips the general multi-byte loop's per-byte shifting
+    // and bounds bookkeeping entirely.
+    #[cfg(feature = "fast-varint")]
+    if let Some((&first, rest)) = buf.split_first() {
+        if first & 0x80 == 0 {
+            return Some((first as u64, rest));
+        }
+    }
+
+    decode_varint_multi_byte(buf)
+}
+
+fn decode_varint_multi_byte(buf: &[u8]) -> Option<(u64, &[u8])> {
+    let mut result: u64 = 0;
+    for (i, &byte) in buf.iter().enumerate() {
+        result |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((result, &buf[i + 1..]));
+        }
+        if i == 9 {
+            return None;
+        }
+    }
+    None
+}
+
+pub fn encode_tag(field_number: u32, wire_type: WireType) -> u64 {
+    ((field_number as u64) << 3) | (wire_type as u64)
+}
+
+/// Maps a signed integer onto the unsigned range so small-magnitude
+/// negative values still varint-encode to few bytes -- plain varint
+/// encoding of a negative `i64` sign-extends it to all ones in the high
+/// bits (see `decode_zigzag`'s doc comment), which is 10 bytes for even
+/// `-1`. Used for `sint32`/`sint64` fields; no field in this crate uses
+/// that encoding, but the transform is exposed standalone for tooling
+/// that needs to size or re-encode one by hand.
+pub fn encode_zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Inverse of [`encode_zigzag`].
+pub fn decode_zigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+pub fn decode_tag(buf: &[u8]) -> Option<(Tag, &[u8])> {
+    let (raw, rest) = decode_varint(buf)?;
+    let wire_type = WireType::from_tag_byte(raw)?;
+    let field_number = u32::try_from(raw >> 3).ok()?;
+    Some((Tag { field_number, wire_type }, rest))
+}
+
+/// Skips a group field's content, starting right after its `StartGroup`
+/// tag. Returns `(content, rest)`, where `content` is exactly the bytes up
+/// to (but not including) the matching `EndGroup` tag -- an ordinary
+/// tag/value stream that can be parsed the same way a message's fields
+/// are, or re-emitted verbatim to preserve an unknown group field -- and
+/// `rest` is whatever follows that `EndGroup` tag.
+///
+/// Every field inside is skipped generically by wire type, recursing into
+/// nested groups of any field number; only an `EndGroup` whose field
+/// number matches `field_number` closes this one. Returns `None` on
+/// truncated input, a missing/mismatched `EndGroup`, or more than
+/// [`MAX_GROUP_NESTING_DEPTH`] levels of nested `StartGroup`s -- without
+/// that cap, a few thousand bytes of back-to-back `StartGroup` tags would
+/// recurse just as deep and overflow the stack, a classic protobuf DoS
+/// vector every production parser caps (this crate's limit matches C++'s
+/// ~100-deep default).
+pub fn skip_group(buf: &[u8], field_number: u32) -> Option<(&[u8], &[u8])> {
+    skip_group_within_depth(buf, field_number, 0)
+}
+
+/// The deepest chain of nested `StartGroup`s [`skip_group`] will follow
+/// before giving up and reporting malformed input, matching C++'s default.
+pub const MAX_GROUP_NESTING_DEPTH: u32 = 100;
+
+fn skip_group_within_depth(buf: &[u8], field_number: u32, depth: u32) -> Option<(&[u8], &[u8])> {
+    if depth >= MAX_GROUP_NESTING_DEPTH {
+        return None;
+    }
+    let mut cursor = buf;
+    loop {
+        let (tag, rest) = decode_tag(cursor)?;
+        match tag.wire_type {
+            WireType::EndGroup => {
+                if tag.field_number != field_number {
+                    return None;
+                }
+                let content_len = buf.len() - cursor.len();
+                return Some((&buf[..content_len], rest));
+            }
+            WireType::StartGroup => {
+                let (_, after) = skip_group_within_depth(rest, tag.field_number, depth + 1)?;
+                cursor = after;
+            }
+            WireType::Varint => {
+                let (_, after) = decode_varint(rest)?;
+                cursor = after;
+            }
+            WireType::Fixed32 => cursor = rest.get(4..)?,
+            WireType::Fixed64 => cursor = rest.get(8..)?,
+            WireType::LengthDelimited => {
+                let (len, after) = decode_varint(rest)?;
+                cursor = after.get(len as usize..)?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn varint_round_trips() {
+        for value in [0u64, 1, 127, 128, 300, u64::MAX] {
+            let mut buf = Vec::new();
+            encode_varint(value, &mut buf);
+            assert_eq!(decode_varint(&buf), Some((value, &[][..])));
+        }
+    }
+
+    #[test]
+    fn fast_path_and_multi_byte_path_agree_on_single_byte_values() {
+        for value in [0u64, 1, 42, 127] {
+            let mut buf = Vec::new();
+            encode_varint(value, &mut buf);
+            assert_eq!(decode_varint(&buf), decode_varint_multi_byte(&buf));
+        }
+    }
+
+    #[test]
+    fn skip_group_returns_content_and_resumes_after_end_group() {
+        let mut buf = Vec::new();
+        encode_varint(encode_tag(1, WireType::Varint), &mut buf);
+        encode_varint(5, &mut buf);
+        let content_end = buf.len();
+        encode_varint(encode_tag(3, WireType::EndGroup), &mut buf);
+        buf.extend_from_slice(b"trailing");
+
+        let (content, rest) = skip_group(&buf, 3).unwrap();
+        assert_eq!(content, &buf[..content_end]);
+        assert_eq!(rest, b"trailing");
+    }
+
+    #[test]
+    fn skip_group_recurses_into_nested_groups() {
+        let mut inner = Vec::new();
+        encode_varint(encode_tag(2, WireType::Varint), &mut inner);
+        encode_varint(7, &mut inner);
+
+        let mut buf = Vec::new();
+        encode_varint(encode_tag(9, WireType::StartGroup), &mut buf);
+        buf.extend_from_slice(&inner);
+        encode_varint(encode_tag(9, WireType::EndGroup), &mut buf);
+        let content_end = buf.len();
+        encode_varint(encode_tag(3, WireType::EndGroup), &mut buf);
+
+        let (content, rest) = skip_group(&buf, 3).unwrap();
+        assert_eq!(content, &buf[..content_end]);
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn skip_group_rejects_mismatched_end_group() {
+        let mut buf = Vec::new();
+        encode_varint(encode_tag(4, WireType::EndGroup), &mut buf);
+        assert_eq!(skip_group(&buf, 3), None);
+    }
+
+    #[test]
+    fn skip_group_rejects_nesting_deeper_than_the_cap_instead_of_overflowing_the_stack() {
+        let mut buf = Vec::new();
+        for _ in 0..(MAX_GROUP_NESTING_DEPTH + 1) {
+            encode_varint(encode_tag(1, WireType::StartGroup), &mut buf);
+        }
+        assert_eq!(skip_group(&buf, 1), None);
+    }
+
+    #[test]
+    fn skip_group_accepts_nesting_exactly_at_the_cap() {
+        let mut buf = Vec::new();
+        for _ in 0..(MAX_GROUP_NESTING_DEPTH - 1) {
+            encode_varint(encode_tag(1, WireType::StartGroup), &mut buf);
+        }
+        for _ in 0..(MAX_GROUP_NESTING_DEPTH - 1) {
+            encode_varint(encode_tag(1, WireType::EndGroup), &mut buf);
+        }
+        encode_varint(encode_tag(1, WireType::EndGroup), &mut buf);
+        assert!(skip_group(&buf, 1).is_some());
+    }
+
+    #[test]
+    fn zigzag_round_trips_small_and_large_magnitudes() {
+        for value in [0i64, 1, -1, 2, -2, i32::MIN as i64, i32::MAX as i64, i64::MIN, i64::MAX] {
+            assert_eq!(decode_zigzag(encode_zigzag(value)), value);
+        }
+    }
+
+    #[test]
+    fn zigzag_keeps_small_negative_values_small() {
+        // The whole point of zigzag: `-1` should cost one varint byte, not
+        // the ten a sign-extended plain varint encoding would need.
+        let mut buf = Vec::new();
+        encode_varint(encode_zigzag(-1), &mut buf);
+        assert_eq!(buf.len(), 1);
+    }
+
+    #[test]
+    fn tag_round_trips() {
+        let mut buf = Vec::new();
+        encode_varint(encode_tag(5, WireType::LengthDelimited), &mut buf);
+        let (tag, rest) = decode_tag(&buf).unwrap();
+        assert_eq!(tag, Tag { field_number: 5, wire_type: WireType::LengthDelimited });
+        assert!(rest.is_empty());
+    }
+}
@@ -0,0 +1,10 @@
+//! A dedicated compile-fail check that mutating a `Map` while an iterator
+//! borrowed from it is still live is rejected by the borrow checker --
+//! the compile-time analog of the debug-mode generation-counter check an
+//! arena-backed `MapView` would need at runtime to catch the same
+//! mistake; see [`protobuf::Map::iter`]'s doc comment.
+#[test]
+fn mutating_a_map_while_iterating_is_a_borrow_error() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/map_iteration_safety/mutating_while_iterating_is_a_borrow_error.rs");
+}
@@ -0,0 +1,12 @@
+// Inserting into `map` while `iter`'s borrow is still live must fail to
+// compile: there's no separate `MapView` handle here that could alias the
+// same storage, so this is the one and only protection against mutating
+// during iteration, and it has to actually fire.
+use protobuf::Map;
+
+fn main() {
+    let mut map: Map<i32, i32> = Map::new();
+    let mut iter = map.iter();
+    map.insert(1, 2);
+    let _ = iter.next();
+}
@@ -0,0 +1,8 @@
+//! A dedicated compile-fail check for `Map`'s sealed `MapKey` bound: a
+//! `bytes`-keyed map must fail with the crate's own diagnostic, not
+//! whichever generic bound error rustc happens to surface first.
+#[test]
+fn bytes_keyed_map_is_rejected_with_a_dedicated_diagnostic() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/map_key_diagnostics/bytes_key_is_rejected.rs");
+}
@@ -0,0 +1,8 @@
+// `Vec<u8>` is a `bytes` field; protobuf never allows `bytes` as a map
+// key, so this must fail to compile with `Map`'s own diagnostic instead
+// of a bare "trait not satisfied".
+use protobuf::Map;
+
+fn main() {
+    let _map: Map<Vec<u8>, i32> = Map::new();
+}
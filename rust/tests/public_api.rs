@@ -0,0 +1,19 @@
+//! Locks down this crate's public surface: every internal module (`arena`,
+//! `message`, `reflect`, ...) is private, and `lib.rs`'s curated `pub use`
+//! list is the only way in. `rustc` already refuses to compile a `pub`
+//! item whose signature mentions a private type (the `private_interfaces`
+//! lint, deny-by-default), so that half of "no internal symbol leaks into
+//! a user-visible signature" is enforced on every build without this
+//! test. What isn't otherwise covered is the downstream-crate view: that
+//! reaching into a module path directly (`protobuf::arena::Arena`,
+//! bypassing the re-export) fails, and that the curated surface is
+//! actually enough to use the crate. `trybuild` exercises both from
+//! outside the crate, the same vantage point a dependent Android crate
+//! would have.
+#[test]
+fn public_api_surface() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/public_api/uses_the_public_reexports.rs");
+    cases.pass("tests/public_api/uses_the_prelude.rs");
+    cases.compile_fail("tests/public_api/cannot_reach_private_modules.rs");
+}
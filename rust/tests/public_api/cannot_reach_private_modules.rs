@@ -0,0 +1,8 @@
+// `arena` is a private `mod` in lib.rs; reaching through its path instead
+// of the `Arena` re-export must not compile, the same as it wouldn't for
+// any other downstream crate.
+use protobuf::arena::Arena;
+
+fn main() {
+    let _ = Arena::new();
+}
@@ -0,0 +1,27 @@
+// `protobuf::prelude::*` alone (no individual re-exports) is enough to
+// name the common field-wrapper types and implement the common traits.
+use protobuf::prelude::*;
+
+struct Handle(i32);
+
+impl CopyFrom for Handle {
+    fn copy_from(&mut self, source: &Self) {
+        self.0 = source.0;
+    }
+}
+
+fn main() {
+    let mut repeated: Repeated<i32> = Repeated::new();
+    repeated.push(1);
+
+    let mut map: Map<i32, i32> = Map::new();
+    map.insert(1, 2);
+
+    let present = Optional::set(5i32);
+    assert!(present.is_set());
+
+    let mut handle = Handle(1);
+    let other = Handle(2);
+    handle.copy_from(&other);
+    assert_eq!(handle.0, 2);
+}
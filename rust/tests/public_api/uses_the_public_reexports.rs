@@ -0,0 +1,30 @@
+// A smoke test that the curated public surface is self-sufficient: a
+// downstream crate pulling in only `pub use` names (no internal module
+// paths) can still allocate, wrap a scalar field, and round-trip a
+// varint.
+use protobuf::{encode_varint, decode_varint, Arena, Optional, PrimitiveMut, Repeated};
+
+fn main() {
+    let arena = Arena::new();
+    let _ = arena.alloc_bytes(4);
+
+    let mut present = Optional::set(5i32);
+    assert!(present.is_set());
+    assert_eq!(present.as_ref(), Some(&5));
+    present = Optional::unset();
+    assert!(!present.is_set());
+
+    let mut counter = 0i64;
+    let mut handle = PrimitiveMut::new(&mut counter);
+    handle.add_assign(3);
+    assert_eq!(handle.get(), 3);
+
+    let repeated: Repeated<i32> = Repeated::from(vec![1, 2, 3]);
+    assert_eq!(repeated.len(), 3);
+
+    let mut buf = Vec::new();
+    encode_varint(300, &mut buf);
+    let (value, rest) = decode_varint(&buf).unwrap();
+    assert_eq!(value, 300);
+    assert!(rest.is_empty());
+}